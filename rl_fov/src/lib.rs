@@ -1,11 +1,8 @@
 // www.adammil.net/blog/v125_roguelike_vision_algorithms.html
 // http://www.roguebasin.com/index.php?title=Comparative_study_of_field_of_view_algorithms_for_2D_grid_based_worlds
 
-#![feature(const_fn)]
-#![feature(const_generics)]
-//#![feature(min_const_generics)]
-#![feature(const_evaluatable_checked)]
-#![feature(int_bits_const)]
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
 pub mod utils;
 
 pub mod bevelled_walls;
@@ -14,7 +11,7 @@ pub mod precalculated_raycasting;
 pub mod rpsc;
 pub mod shadow_casting;
 
-use rl_utils::{Area, Coord};
+use rl_utils::{Area, BitMap, Coord, Distance};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 pub enum FovCallbackEnum {
@@ -47,6 +44,8 @@ pub enum VisionShape {
     CircleStrict,
     Diamond,
     Square,
+    /// Everything within the radius as measured by the [Distance] metric.
+    Distance(Distance),
 }
 impl VisionShape {
     const fn in_radius(self, row: usize, cell: usize, radius: usize) -> bool {
@@ -56,6 +55,39 @@ impl VisionShape {
             VisionShape::CircleStrict => (row * row) + (cell * cell) <= radius * radius,
             VisionShape::Diamond => row + cell <= radius - row,
             VisionShape::Square => row <= radius,
+            VisionShape::Distance(metric) => metric.within_offset(row, cell, radius),
+        }
+    }
+}
+
+/// Keeps the result of a field of view in a [BitMap], to be used as callback type of the [Fov] and [Los]
+/// implementations together with [FovBitMaps::fov_func].
+///
+/// Tiles outside of the area of `transparent` are treated as blocking.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FovBitMaps {
+    pub transparent: BitMap,
+    pub visible:     BitMap,
+}
+impl FovBitMaps {
+    /// Creates the bitmaps from the transparent tiles, for example from `Map::transparent_bitmap`.
+    pub fn new(transparent: BitMap) -> Self {
+        let visible = BitMap::new(transparent.area.size).with_offset(transparent.area.position);
+        FovBitMaps { transparent, visible, }
+    }
+
+    /// Answers whether `c` blocks sight, and marks visible tiles within the area of `visible`.
+    ///
+    /// Always returns true for [FovCallbackEnum::SetVisible], like the other callbacks.
+    pub fn fov_func(&mut self, c: Coord, fe: FovCallbackEnum) -> bool {
+        match fe {
+            FovCallbackEnum::IsBlocked => !self.transparent.get(c),
+            FovCallbackEnum::SetVisible(visible) => {
+                if visible && self.visible.area.point_within(c) {
+                    self.visible.set(c, true);
+                }
+                true
+            },
         }
     }
 }
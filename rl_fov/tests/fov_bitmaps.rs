@@ -0,0 +1,69 @@
+use rl_fov::{shadow_casting::ShadowCasting, Fov, FovBitMaps, FovCallbackEnum, VisionShape};
+use rl_utils::{Area, BitMap, Coord, Distance};
+
+/// A room at (10,20) with a wall from (16,21) down to (16,25).
+fn room() -> FovBitMaps {
+    let mut transparent = BitMap::new((12, 7).into()).with_offset((10, 20).into());
+    transparent.fill(true);
+    for y in 21..=25 {
+        transparent.set((16, y).into(), false);
+    }
+    FovBitMaps::new(transparent)
+}
+
+fn shadow_casting(bitmaps: &mut FovBitMaps, area: Area, radius: usize, vision: VisionShape) {
+    ShadowCasting { symmetric: false,
+                    area,
+                    radius,
+                    vision,
+                    cb_type: bitmaps,
+                    callback: FovBitMaps::fov_func }.fov((12, 23).into());
+}
+
+#[test]
+fn shadow_casting_into_bitmaps() {
+    let mut bitmaps = room();
+    // The area of the field of view reaches past the bitmaps on every side.
+    shadow_casting(&mut bitmaps, Area::new((0, 0).into(), (40, 40).into()), 20, VisionShape::Square);
+    let visible = |x, y| bitmaps.visible.get(Coord::new(x, y));
+
+    // Everything in front of the wall and the wall itself, but not the source.
+    for y in 20..27 {
+        for x in 10..=16 {
+            assert_eq!(visible(x, y), (x, y) != (12, 23), "{:?}", (x, y));
+        }
+    }
+    // Behind the wall only the tiles next to its ends can be seen.
+    assert!(visible(17, 20) && visible(17, 26));
+    assert!(!visible(17, 21) && !visible(21, 23));
+    assert_eq!(bitmaps.visible.count_ones(), 50);
+}
+
+#[test]
+fn distance_vision() {
+    let area = Area::new((0, 0).into(), (40, 40).into());
+    for metric in [Distance::Chebyshev, Distance::Manhattan, Distance::Euclidean, Distance::Octile] {
+        let mut bitmaps = room();
+        shadow_casting(&mut bitmaps, area, 3, VisionShape::Distance(metric));
+        for p in bitmaps.visible.iter() {
+            assert!(metric.within((12, 23).into(), p, 3), "{:?} {:?}", metric, p);
+        }
+        // The tiles in front of the wall at a distance of 3 along an axis are always in reach.
+        assert!(bitmaps.visible.get((15, 23).into()) && bitmaps.visible.get((12, 20).into()));
+    }
+
+    // Chebyshev distance is the square vision.
+    let (mut square, mut chebyshev) = (room(), room());
+    shadow_casting(&mut square, area, 3, VisionShape::Square);
+    shadow_casting(&mut chebyshev, area, 3, VisionShape::Distance(Distance::Chebyshev));
+    assert_eq!(square.visible, chebyshev.visible);
+}
+
+#[test]
+fn outside_of_the_bitmaps() {
+    let mut bitmaps = room();
+    let outside = Coord::new(9, 23);
+    assert!(bitmaps.fov_func(outside, FovCallbackEnum::IsBlocked));
+    assert!(bitmaps.fov_func(outside, FovCallbackEnum::SetVisible(true)));
+    assert!(bitmaps.visible.is_clear());
+}
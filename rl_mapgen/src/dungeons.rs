@@ -24,7 +24,7 @@ use std::fmt;
 
 use rand::{prelude::SliceRandom, rngs::SmallRng, FromEntropy, Rng, SeedableRng};
 
-use rl_utils::{Area, Coord, Map, MapIterator, MapObject, MovementCost};
+use rl_utils::{Area, Coord, Distance, Map, MapIterator, MapObject, MovementCost};
 
 use crate::{
     corridors::{walker_corridor, CorridorFunction},
@@ -243,6 +243,7 @@ pub struct Dungeon {
     pub(crate) corridors:    Vec<Vec<Coord>>,
     pub(crate) stairs:       Vec<Coord>,
     pub(crate) spawn_points: Vec<(Coord, Tile)>,
+    pub(crate) distance:     Distance,
 }
 impl Dungeon {
     /// This creates this struct, but should not be used by the user.
@@ -257,11 +258,19 @@ impl Dungeon {
                               secret_rooms: vec![],
                               corridors: vec![],
                               stairs: vec![],
-                              spawn_points: vec![] };
+                              spawn_points: vec![],
+                              distance: Distance::Chebyshev };
         d.map.fill(Tile::Transparent);
         d
     }
 
+    /// Sets how the heatmaps of [SpawnPlacements](../spawn_placement/enum.SpawnPlacements.html)
+    /// measure distances, defaults to `Distance::Chebyshev`.
+    pub fn with_distance(mut self, distance: Distance) -> Dungeon {
+        self.distance = distance;
+        self
+    }
+
     /// Add stairs to the map.
     ///
     /// The Target [Tile](../utils/tile/enum.Tile.html) represents the type of tile whichshould be replaced.
@@ -383,7 +392,7 @@ impl Dungeon {
                             }
 
                             if empty_area_walled {
-                                let secret_area = Area::new(search_point, (1, 1).into());
+                                let secret_area = Area::new(search_point, (2, 2).into());
                                 let door_mod: [Coord; 8] = [(0, -1).into(),
                                                             (1, -1).into(),
                                                             (-1, 0).into(),
@@ -485,16 +494,14 @@ impl Dungeon {
     ///
     /// Other iterators give access to other created features.
     pub fn iter(&'_ self) -> MapIterator<'_, Tile> {
-        MapIterator { pos: 0, size: self.area.size, start: self.area.position, map: &self.map.data, }
+        MapIterator::new(&self.map.data, self.area.size).with_offset(self.area.position)
     }
 
     /// Similar to [iter()](struct.Dungeon.html#method.iter), with the difference that this iterates over a subset of the total map.
     pub fn area_iter(&'_ self, area: Area) -> Option<MapIterator<'_, Tile>> {
-        if self.area.area_within(area) {
-            Some(MapIterator { pos: 0, size: area.size, start: area.position, map: &self.map.data, })
-        } else {
-            None
-        }
+        // The map itself starts at (0,0), so it has to be iterated in local positions.
+        let local = Area::new(area.position - self.area.position, area.size);
+        self.map.area_iter(local).map(|iter| iter.with_offset(area.position))
     }
 
     /// Returns the area covered by this dungeon
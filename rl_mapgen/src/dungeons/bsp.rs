@@ -1,9 +1,8 @@
 use std::vec::Vec;
 
 use rand::{rngs::SmallRng, Rng, SeedableRng};
-use triangulation::{Delaunay, Point};
 
-use rl_utils::{Area, Coord};
+use rl_utils::{delaunay, Area, Coord};
 
 /// Generates a multi-room dungeon via the
 /// [BSP](http://www.roguebasin.com/index.php?title=Basic_BSP_Dungeon_generation) Algorithm. Then
@@ -33,31 +32,6 @@ impl SplitDir {
     }
 }
 
-trait BspSplit {
-    fn split(&self, dir: SplitDir, percentage: isize) -> (Area, Area);
-}
-impl BspSplit for Area {
-    // Too tired to fix this abomination for now...
-    fn split(&self, dir: SplitDir, percentage: isize) -> (Area, Area) {
-        assert!(percentage < 100);
-
-        match dir {
-            SplitDir::Horizontal => {
-                let yd = (self.size.y * percentage) / 100;
-                (Area { position: self.position, size: Coord { x: self.size.x, y: yd, }, },
-                 Area { position: Coord { x: self.position.x, y: self.position.y + yd, },
-                        size:     Coord { x: self.size.x, y: self.size.y - yd, }, })
-            },
-            SplitDir::Vertical => {
-                let xd = (self.size.x * percentage) / 100;
-                (Area { position: self.position, size: Coord { x: xd, y: self.size.y, }, },
-                 Area { position: Coord { x: self.position.x + xd, y: self.position.y, },
-                        size:     Coord { x: self.size.x - xd, y: self.size.y, }, })
-            },
-        }
-    }
-}
-
 #[derive(PartialEq, Debug, Clone)]
 struct BspData {
     pub area:       Area,
@@ -125,8 +99,11 @@ impl Split for BspData {
         } else {
             None
         } {
+            let (a1, a2) = match split_dir {
+                SplitDir::Horizontal => self.area.split_y((self.area.size.y * perc) / 100),
+                SplitDir::Vertical => self.area.split_x((self.area.size.x * perc) / 100),
+            }?;
             self.split_dir = Some(split_dir);
-            let (a1, a2) = self.area.split(split_dir, perc);
             Some((BspData::new(a1), BspData::new(a2)))
         } else {
             None
@@ -391,43 +368,38 @@ impl DungeonBuilder for Bsp {
 
         // we now have an minimal connected dungeon, so we redo the the rtiangulation, bu now we
         // include the minor rooms.
-        let mut points: Vec<Point> = vec![];
+        let mut points = vec![];
         let mut ids = vec![];
         for node in bsp.leaf_iter().collect::<Vec<_>>() {
             if let Some(data) = bsp.get_data(node) {
                 if let Some(ref room) = data.room {
-                    points.push((room.map.area.center() + room.area.position).into());
+                    points.push(room.map.area.center() + room.area.position);
                     ids.push(node.id);
                 }
             }
         }
-        let result = Delaunay::new(&points).expect("No triangulation exists.");
 
         // for a set percentage of the edges, create corridors
-        let mut triangles2 = result.dcel.vertices.clone().into_iter();
-        triangles2.next();
-        for e1 in result.dcel.vertices {
-            if let Some(e2) = triangles2.next() {
-                if rng.gen_range(0, 100) < self.extra_corridor_chance {
-                    let e1_leaf = ids[e1];
-                    let e2_leaf = ids[e2];
-
-                    let e1_center = output.rooms[e1].center();
-                    let e2_center = output.rooms[e2].center();
-
-                    let mut ap1 = None;
-                    let mut ap2 = None;
-                    if let Some(room) = &mut bsp.data[e1_leaf].room {
-                        ap1 = room.create_access_point_from(e2_center);
-                    }
-                    if let Some(room) = &mut bsp.data[e2_leaf].room {
-                        ap2 = room.create_access_point_from(e1_center);
-                    }
+        for (e1, e2) in delaunay(&points) {
+            if rng.gen_range(0, 100) < self.extra_corridor_chance {
+                let e1_leaf = ids[e1];
+                let e2_leaf = ids[e2];
+
+                let e1_center = output.rooms[e1].center();
+                let e2_center = output.rooms[e2].center();
+
+                let mut ap1 = None;
+                let mut ap2 = None;
+                if let Some(room) = &mut bsp.data[e1_leaf].room {
+                    ap1 = room.create_access_point_from(e2_center);
+                }
+                if let Some(room) = &mut bsp.data[e2_leaf].room {
+                    ap2 = room.create_access_point_from(e1_center);
+                }
 
-                    if let Some(a1) = ap1 {
-                        if let Some(a2) = ap2 {
-                            output.add_corridor((self.default_corridor)(a1, a2));
-                        }
+                if let Some(a1) = ap1 {
+                    if let Some(a2) = ap2 {
+                        output.add_corridor((self.default_corridor)(a1, a2));
                     }
                 }
             }
@@ -2,11 +2,7 @@ use std::vec::Vec;
 
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
-use rl_utils::{
-    ca_generate,
-    dijkstra_map::{DijkstraMap, DijkstraMapValue},
-    Area, CATile, CAparams, Coord, Map, CA,
-};
+use rl_utils::{ca_generate, Area, CATile, CAparams, Coord, Distance, Map, CA};
 
 use crate::{
     dungeons::{Dungeon, DungeonBuilder, DungeonConfigurer, DungeonParams},
@@ -79,25 +75,13 @@ impl DungeonBuilder for CellularAutomata {
         connect_map(&mut output.map);
 
         // Search for room areas
-        // Since we have only one room with this map, now we use a dijkstra map to search for open
-        // areas and designate them as rooms.
-        let dmap = DijkstraMap::new(output.area.size).seed_map(|c| {
-                                                         if output.map[c] == Tile::Wall {
-                                                             DijkstraMapValue::Goal
-                                                         } else {
-                                                             DijkstraMapValue::Default
-                                                         }
-                                                     })
-                                                     .calculate()
-                                                     .invert();
-
-        for (c, dv) in dmap.map.iter() {
-            let mut found = false;
-            match dv {
-                DijkstraMapValue::Goal => found = true,
-                DijkstraMapValue::NonGoal(ng) if ng >= 3 => found = true,
-                _ => (),
-            }
+        // Since we have only one room with this map, now we use the distance towards the walls to
+        // search for open areas and designate them as rooms.
+        let dist = output.map.distance_transform(Distance::Chebyshev, |_, t| *t == Tile::Wall);
+        let max = dist.iter().map(|(_, d)| d).filter(|d| *d != u32::MAX).max().unwrap_or(0);
+
+        for (c, d) in dist.iter() {
+            let found = output.map[c] != Tile::Wall && d != u32::MAX && max - d >= 3;
             if found {
                 let mut used = false;
                 for r in &output.rooms {
@@ -107,7 +91,7 @@ impl DungeonBuilder for CellularAutomata {
                 }
                 if !used {
                     let md = (4, 4).into();
-                    let area = Area::new(c - md, md + md);
+                    let area = Area::new(c - md, md + md + (1, 1).into());
                     if output.area.area_within(area) {
                         output.rooms.push(area);
                     }
@@ -1,11 +1,9 @@
 use std::vec::Vec;
 
 use min_max_macros::min;
-use pathfinding::undirected::kruskal::kruskal_indices;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
-use triangulation::{Delaunay, Point};
 
-use rl_utils::{Area, Coord};
+use rl_utils::{delaunay, minimum_spanning_tree, Area, Coord};
 
 use crate::{
     corridors::{create_corridor, walker_corridor, CorridorFunction},
@@ -205,30 +203,17 @@ impl DungeonBuilder for Stack {
             }
 
             // collect the major room centers
-            let mut points: Vec<Point> = vec![];
+            let mut points = vec![];
             for room in room_vector.iter().take(minor_start) {
-                points.push(room.map.area.center().into());
+                points.push(room.map.area.center());
             }
-            // triangulate
-            let result = Delaunay::new(&points);
-            if result.is_none() {
+            if points.len() < 3 {
                 continue;
             }
-            let result = result.expect("No triangulation exists. [1]");
-
-            // collect the triangulated edges
-            let mut edges = vec![];
-            let mut triangles2 = result.dcel.vertices.clone().into_iter();
-            triangles2.next();
-            for i1 in result.dcel.vertices {
-                if let Some(i2) = triangles2.next() {
-                    edges.push((i1, i2, ()));
-                }
-            }
 
             // do an minimal spanning tree, and create those corridors
             let mut corridors = vec![];
-            for (e1, e2, _) in kruskal_indices(points.len(), edges.as_slice()) {
+            for (e1, e2) in minimum_spanning_tree(&points) {
                 let e1_center = room_vector[e1].map.area.center();
                 let e2_center = room_vector[e2].map.area.center();
                 let ap1 = room_vector[e1].create_access_point_from(e2_center);
@@ -243,27 +228,22 @@ impl DungeonBuilder for Stack {
 
             // we now have an minimal connected dungeon, so we redo the the rtiangulation, bu now we
             // include the minor rooms.
-            let mut points: Vec<Point> = vec![];
+            let mut points = vec![];
             for r in &room_vector {
-                points.push(r.area.center().into());
+                points.push(r.area.center());
             }
-            let result = Delaunay::new(&points).expect("No triangulation exists. [2]");
 
             // for a set percentage of the edges, create corridors
-            let mut triangles2 = result.dcel.vertices.clone().into_iter();
-            triangles2.next();
-            for e1 in result.dcel.vertices {
-                if let Some(e2) = triangles2.next() {
-                    if rng.gen_range(0, 100) < self.corridor_chance {
-                        let e1_center = room_vector[e1].map.area.center();
-                        let e2_center = room_vector[e2].map.area.center();
-                        let ap1 = room_vector[e1].create_access_point_from(e2_center);
-                        let ap2 = room_vector[e2].create_access_point_from(e1_center);
-
-                        if let Some(ap1) = ap1 {
-                            if let Some(ap2) = ap2 {
-                                corridors.push((self.default_corridor)(ap1, ap2));
-                            }
+            for (e1, e2) in delaunay(&points) {
+                if rng.gen_range(0, 100) < self.corridor_chance {
+                    let e1_center = room_vector[e1].map.area.center();
+                    let e2_center = room_vector[e2].map.area.center();
+                    let ap1 = room_vector[e1].create_access_point_from(e2_center);
+                    let ap2 = room_vector[e2].create_access_point_from(e1_center);
+
+                    if let Some(ap1) = ap1 {
+                        if let Some(ap2) = ap2 {
+                            corridors.push((self.default_corridor)(ap1, ap2));
                         }
                     }
                 }
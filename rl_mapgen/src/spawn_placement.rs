@@ -1,9 +1,8 @@
-use pathfinding::directed::astar::astar;
 use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
 
 use rl_utils::{
     dijkstra_map::{DijkstraMap, DijkstraMapValue},
-    Area, Coord, Map, MapMovement, MapObject, MovementCost,
+    Area, Coord, Map, MapMovement, MapObject, MovementCost, PathConfig, PoissonDisk,
 };
 
 use crate::{dungeons::Dungeon, utils::Tile};
@@ -48,6 +47,13 @@ pub enum SpawnPlacements<'a> {
     /// A Heatmap is generated with each Near [Tile](../utils/tile/enum.Tile.html) as the coolest points. A spawn-point is
     /// selected from a random low point of the specific [Tile](../utils/tile/enum.Tile.html).
     Near(Tile),
+    /// The map is sampled with tiles at least the given distance apart, see
+    /// [PoissonDisk](../../rl_utils/poisson/struct.PoissonDisk.html), and the sample farthest from
+    /// all other features is selected. Repeated spawns spread evenly over the map.
+    ///
+    /// Every spawn samples the whole map again, so each placement costs about as much as a pass
+    /// over the map. No spawn-point is returned when the distance is not positive.
+    Spread(f64),
     /// A custom spawn-point algorithm taking a closure.
     Custom(&'a SpawnCallback),
 }
@@ -62,6 +68,7 @@ impl<'a> std::fmt::Display for SpawnPlacements<'a> {
             SpawnPlacements::RandomCorridor => write!(f, "SpawnPlacements::RandomCorridor"),
             SpawnPlacements::Random => write!(f, "SpawnPlacements::Random"),
             SpawnPlacements::Near(tile) => write!(f, "SpawnPlacements::Near: {}", tile.to_string()),
+            SpawnPlacements::Spread(radius) => write!(f, "SpawnPlacements::Spread: {}", radius),
             SpawnPlacements::Custom(_) => write!(f, "SpawnPlacements::Custom Function"),
         }
     }
@@ -70,9 +77,10 @@ impl<'a> SpawnPlacements<'a> {
     fn avoid_critical_path(&self,
                            target: Tile,
                            features: &[(Coord, Tile)],
-                           map: &Map<Tile>,
+                           dungeon: &Dungeon,
                            seed: u64)
                            -> Option<Coord> {
+        let (map, distance) = (&dungeon.map, dungeon.distance);
         let mut rng = SmallRng::seed_from_u64(seed);
         let walkable = if let MovementCost::Possible(_) = target.is_walkable() { true } else { false };
 
@@ -90,11 +98,8 @@ impl<'a> SpawnPlacements<'a> {
                     break;
                 }
 
-                if let Some((result, _)) = astar(s1,
-                                                 |p| map.walkable_tiles(*p, MapMovement::Orthogonal),
-                                                 |p| p.pyth(*s2) as usize,
-                                                 |p| *p == *s2)
-                {
+                let config = PathConfig::new().with_movement(MapMovement::Orthogonal).with_distance(distance);
+                if let Some(result) = map.astar(*s1, *s2, &config) {
                     for c in result {
                         critical_path.push(c);
                     }
@@ -116,7 +121,7 @@ impl<'a> SpawnPlacements<'a> {
         for c in critical_path {
             dmap = dmap.with_goal(c);
         }
-        let dmap = dmap.calculate();
+        let dmap = dmap.with_distance(distance).calculate_weighted();
 
         let mut target_list = vec![];
         for (c, _) in dmap.map.iter() {
@@ -149,9 +154,10 @@ impl<'a> SpawnPlacements<'a> {
                      spawn_type: Tile,
                      target: Tile,
                      features: &[(Coord, Tile)],
-                     map: &Map<Tile>,
+                     dungeon: &Dungeon,
                      seed: u64)
                      -> Option<Coord> {
+        let (map, distance) = (&dungeon.map, dungeon.distance);
         let mut rng = SmallRng::seed_from_u64(seed);
         let walkable = if let MovementCost::Possible(_) = target.is_walkable() { true } else { false };
 
@@ -171,7 +177,7 @@ impl<'a> SpawnPlacements<'a> {
                 dmap = dmap.with_goal(*c);
             }
         }
-        let dmap = dmap.calculate();
+        let dmap = dmap.with_distance(distance).calculate_weighted();
 
         let mut target_list = vec![];
         for (c, _) in dmap.map.iter() {
@@ -200,9 +206,10 @@ impl<'a> SpawnPlacements<'a> {
                        spawn_type: Tile,
                        target: Tile,
                        features: &[(Coord, Tile)],
-                       map: &Map<Tile>,
+                       dungeon: &Dungeon,
                        seed: u64)
                        -> Option<Coord> {
+        let (map, distance) = (&dungeon.map, dungeon.distance);
         let mut rng = SmallRng::seed_from_u64(seed);
         let walkable = if let MovementCost::Possible(_) = target.is_walkable() { true } else { false };
 
@@ -222,7 +229,7 @@ impl<'a> SpawnPlacements<'a> {
                 dmap = dmap.with_goal(*c);
             }
         }
-        let dmap = dmap.calculate();
+        let dmap = dmap.with_distance(distance).calculate_weighted();
 
         let mut target_list = vec![];
         for (c, _) in dmap.map.iter() {
@@ -315,9 +322,10 @@ impl<'a> SpawnPlacements<'a> {
             spawn_type: Tile,
             target: Tile,
             features: &[(Coord, Tile)],
-            map: &Map<Tile>,
+            dungeon: &Dungeon,
             seed: u64)
             -> Option<Coord> {
+        let (map, distance) = (&dungeon.map, dungeon.distance);
         let mut rng = SmallRng::seed_from_u64(seed);
         let mut near_vec = vec![];
 
@@ -339,10 +347,10 @@ impl<'a> SpawnPlacements<'a> {
         for y in 0..map.area.size.y {
             for x in 0..map.area.size.x {
                 if map[(x, y)] == target {
-                    let mut best = (Coord::new(0, 0), std::isize::MAX);
+                    let mut best = (Coord::new(0, 0), std::f64::MAX);
 
                     for c in &near_vec {
-                        let pyth = c.pyth((x, y).into());
+                        let pyth = c.distance((x, y).into(), distance);
                         if pyth < best.1 {
                             best = ((x, y).into(), pyth);
                         }
@@ -355,9 +363,9 @@ impl<'a> SpawnPlacements<'a> {
         }
 
         if !target_list.is_empty() {
-            if let Some(min) = target_list.iter().min_by(|(_, p1), (_, p2)| p1.cmp(p2)).map(|t| t.1) {
+            if let Some(min) = target_list.iter().min_by(|(_, p1), (_, p2)| p1.total_cmp(p2)).map(|t| t.1) {
                 let target_list =
-                    target_list.iter().filter(|(_, cost)| *cost <= min + 1).map(|(c, _)| *c).collect::<Vec<_>>();
+                    target_list.iter().filter(|(_, cost)| *cost <= min + 1.0).map(|(c, _)| *c).collect::<Vec<_>>();
                 target_list.choose(&mut rng).cloned()
             } else {
                 None
@@ -367,6 +375,28 @@ impl<'a> SpawnPlacements<'a> {
         }
     }
 
+    fn spread(&self,
+              radius: f64,
+              target: Tile,
+              features: &[(Coord, Tile)],
+              dungeon: &Dungeon,
+              seed: u64)
+              -> Option<Coord> {
+        if radius.is_nan() || radius <= 0.0 {
+            return None;
+        }
+        let (map, distance) = (&dungeon.map, dungeon.distance);
+        let samples = PoissonDisk::new(map.area, radius, seed).sample_map(map, |t| *t == target);
+        samples.into_iter()
+               .filter(|c| !features.iter().any(|(f, _)| f == c))
+               .map(|c| {
+                   let nearest = features.iter().map(|(f, _)| c.distance(*f, distance)).fold(f64::MAX, f64::min);
+                   (c, nearest)
+               })
+               .max_by(|(_, a), (_, b)| a.total_cmp(b))
+               .map(|(c, _)| c)
+    }
+
     pub fn place(self,
                  spawn_type: Tile,
                  target: Tile,
@@ -375,14 +405,15 @@ impl<'a> SpawnPlacements<'a> {
                  seed: u64)
                  -> Option<Coord> {
         match self {
-            SpawnPlacements::AvoidCriticalPath => self.avoid_critical_path(target, features, &dungeon.map, seed),
-            SpawnPlacements::AvoidSimilar => self.avoid_similar(spawn_type, target, features, &dungeon.map, seed),
-            SpawnPlacements::ClusterSimilar => self.cluster_similar(spawn_type, target, features, &dungeon.map, seed),
+            SpawnPlacements::AvoidCriticalPath => self.avoid_critical_path(target, features, dungeon, seed),
+            SpawnPlacements::AvoidSimilar => self.avoid_similar(spawn_type, target, features, dungeon, seed),
+            SpawnPlacements::ClusterSimilar => self.cluster_similar(spawn_type, target, features, dungeon, seed),
             SpawnPlacements::RandomRoom => self.random_room(&dungeon.rooms, seed),
             SpawnPlacements::RandomSecretRoom => self.random_secret_room(&dungeon.secret_rooms, seed),
             SpawnPlacements::RandomCorridor => self.random_corridor(&dungeon.corridors, seed),
             SpawnPlacements::Random => self.random(target, &dungeon.map, seed),
-            SpawnPlacements::Near(tile) => self.near(tile, spawn_type, target, features, &dungeon.map, seed),
+            SpawnPlacements::Near(tile) => self.near(tile, spawn_type, target, features, dungeon, seed),
+            SpawnPlacements::Spread(radius) => self.spread(radius, target, features, dungeon, seed),
             SpawnPlacements::Custom(func) => func(spawn_type, target, features, &dungeon.map, seed),
         }
     }
@@ -28,7 +28,7 @@ pub fn separate_areas(bounds: Area, area_list: &[Area]) -> Vec<Area> {
             if velocity.x != 0 || velocity.y != 0 {
                 separated = true;
 
-                if bounds.point_within(a.position + velocity) && bounds.point_within(a.position + a.size + velocity) {
+                if bounds.area_within(Area::new(a.position + velocity, a.size)) {
                     a.position.x += velocity.x;
                     a.position.y += velocity.y;
                 }
@@ -0,0 +1,43 @@
+use rl_mapgen::{
+    dungeons::{DungeonBuilder, DungeonConfigurer, DungeonParams},
+    utils::Tile,
+    CellularAutomata,
+};
+
+/// Taken with the cell by cell rule from before the summed-area table, with windows clipped to
+/// the map.
+const SNAPSHOT: [&str; 24] = ["....#####..#..#####....##..###.......###########",
+                              "....#####..#..#####....##..###.......###########",
+                              "....#####..#..######...##..####.......#########.",
+                              "....#####..#...#####.......####.......######....",
+                              "....#####......######......####........####.....",
+                              "....#####.......######.....####.........##....##",
+                              ".....###........########....##...............###",
+                              "...........###...########...................####",
+                              "####.......###.....#######...........####....###",
+                              "#####.....#####......#####....#......#####....##",
+                              "..#####...######......###....###......#####....#",
+                              "...#####..######..............###......#####...#",
+                              "....####..######..##...........#........#####..#",
+                              "....###....####....#................##...####..#",
+                              "...................................####...##....",
+                              "...................................####.........",
+                              "###...........#...####.............#####......#.",
+                              "#######.......#...####...###.......#####......#.",
+                              "#####....................####..#...#####........",
+                              "#####.....................##......######...##...",
+                              "#####...#......#####..............######..####..",
+                              "#####..###...########..#.........#######..####..",
+                              "#####..##############..########..#######...##...",
+                              "#####...############...########...#####........."];
+
+#[test]
+fn snapshot() {
+    let params = DungeonParams::new_with_seed(48, 24, 7);
+    let dungeon = CellularAutomata::new(48, 24).generate_with_params(params);
+    let mut rows = vec![String::new(); 24];
+    for (c, tile) in dungeon.iter() {
+        rows[c.y as usize].push(if tile == Tile::Wall { '#' } else { '.' });
+    }
+    assert_eq!(rows, SNAPSHOT);
+}
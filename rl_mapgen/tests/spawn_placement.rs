@@ -0,0 +1,18 @@
+use rl_mapgen::{
+    dungeons::{DungeonBuilder, DungeonConfigurer, DungeonParams},
+    utils::Tile,
+    CellularAutomata, SpawnPlacements,
+};
+
+#[test]
+fn spread() {
+    let params = DungeonParams::new_with_seed(48, 24, 7);
+    let dungeon = CellularAutomata::new(48, 24).generate_with_params(params);
+    let place = |radius| SpawnPlacements::Spread(radius).place(Tile::Stairs, Tile::Floor, &[], &dungeon, 3);
+
+    let spawn = place(4.0).unwrap();
+    assert!(dungeon.iter().any(|(c, t)| c == spawn && t == Tile::Floor));
+    assert_eq!(place(0.0), None);
+    assert_eq!(place(-1.0), None);
+    assert_eq!(place(f64::NAN), None);
+}
@@ -34,22 +34,159 @@ impl Area {
             && self.position.y + self.size.y >= area.position.y + area.size.y
     }
 
+    /// Returns true when both areas share at least one tile.
     pub fn overlaps(&self, other: Area) -> bool {
-        self.point_within(other.position)
-            || self.point_within(other.position + (other.size.x, 0).into())
-            || self.point_within(other.position + (0, other.size.y).into())
-            || self.point_within(other.position + other.size)
-            || self.point_within(other.center())
-            || self.position.x >= other.position.x
-                && self.position.y >= other.position.y
-                && self.position.x <= other.position.x + other.size.y
-                && self.position.y <= other.position.y + other.size.x
-            || self.position.x + self.size.y >= other.position.x
-                && self.position.y + self.size.x >= other.position.y
-                && self.position.x + self.size.y <= other.position.x + other.size.y
-                && self.position.y + self.size.x <= other.position.y + other.size.x
+        self.intersect(other).is_some()
     }
 
+    /// Returns true when the area holds no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.size.x <= 0 || self.size.y <= 0
+    }
+
+    /// The tiles shared by both areas, or None when they do not overlap.
+    pub fn intersect(&self, other: Area) -> Option<Area> {
+        let start = Coord::new(
+            self.position.x.max(other.position.x),
+            self.position.y.max(other.position.y),
+        );
+        let end = Coord::new(
+            (self.position.x + self.size.x).min(other.position.x + other.size.x),
+            (self.position.y + self.size.y).min(other.position.y + other.size.y),
+        );
+        let area = Area::new(start, end - start);
+        if area.is_empty() {
+            None
+        } else {
+            Some(area)
+        }
+    }
+
+    /// The smallest area containing both areas.
+    pub fn union(&self, other: Area) -> Area {
+        let start = Coord::new(
+            self.position.x.min(other.position.x),
+            self.position.y.min(other.position.y),
+        );
+        let end = Coord::new(
+            (self.position.x + self.size.x).max(other.position.x + other.size.x),
+            (self.position.y + self.size.y).max(other.position.y + other.size.y),
+        );
+        Area::new(start, end - start)
+    }
+
+    /// Shrinks the area by `amount` tiles on every side, or None when nothing is left.
+    pub fn inset(&self, amount: isize) -> Option<Area> {
+        let area = Area::new(
+            self.position + (amount, amount).into(),
+            self.size - (2 * amount, 2 * amount).into(),
+        );
+        if area.is_empty() {
+            None
+        } else {
+            Some(area)
+        }
+    }
+
+    /// Grows the area by `amount` tiles on every side.
+    pub fn outset(&self, amount: isize) -> Area {
+        Area::new(
+            self.position - (amount, amount).into(),
+            self.size + (2 * amount, 2 * amount).into(),
+        )
+    }
+
+    /// Splits the area into a left part `at` tiles wide and a right part, or None when one of the
+    /// parts would be empty.
+    pub fn split_x(&self, at: isize) -> Option<(Area, Area)> {
+        if at <= 0 || at >= self.size.x {
+            return None;
+        }
+        Some((
+            Area::new(self.position, (at, self.size.y).into()),
+            Area::new(
+                self.position + (at, 0).into(),
+                (self.size.x - at, self.size.y).into(),
+            ),
+        ))
+    }
+
+    /// Splits the area into a top part `at` tiles high and a bottom part, or None when one of the
+    /// parts would be empty.
+    pub fn split_y(&self, at: isize) -> Option<(Area, Area)> {
+        if at <= 0 || at >= self.size.y {
+            return None;
+        }
+        Some((
+            Area::new(self.position, (self.size.x, at).into()),
+            Area::new(
+                self.position + (0, at).into(),
+                (self.size.x, self.size.y - at).into(),
+            ),
+        ))
+    }
+
+    /// Splits the area into a grid of `columns` by `rows` cells, row by row.
+    ///
+    /// When the size does not divide evenly, the first columns and rows are one tile larger.
+    pub fn split_grid(&self, columns: isize, rows: isize) -> Vec<Area> {
+        assert!(columns > 0 && rows > 0);
+        let edges = |start: isize, size: isize, parts: isize| -> Vec<isize> {
+            (0..=parts)
+                .map(|i| start + i * (size / parts) + i.min(size % parts))
+                .collect()
+        };
+        let xs = edges(self.position.x, self.size.x, columns);
+        let ys = edges(self.position.y, self.size.y, rows);
+
+        let mut cells = Vec::with_capacity((columns * rows) as usize);
+        for y in ys.windows(2) {
+            for x in xs.windows(2) {
+                cells.push(Area::new(
+                    (x[0], y[0]).into(),
+                    (x[1] - x[0], y[1] - y[0]).into(),
+                ));
+            }
+        }
+        cells
+    }
+
+    /// The top left, top right, bottom right and bottom left tile of the area.
+    pub fn corners(&self) -> [Coord; 4] {
+        let last = self.position + self.size - (1, 1).into();
+        [
+            self.position,
+            (last.x, self.position.y).into(),
+            last,
+            (self.position.x, last.y).into(),
+        ]
+    }
+
+    /// Iterates clockwise over the tiles along the border of the area, starting at the top left
+    /// tile. Every tile is returned once.
+    pub fn perimeter(&self) -> impl Iterator<Item = Coord> {
+        let [top_left, top_right, bottom_right, bottom_left] = self.corners();
+        let empty = self.is_empty();
+        let (single_row, single_column) = (self.size.y == 1, self.size.x == 1);
+
+        let top = (top_left.x..=top_right.x).map(move |x| Coord::new(x, top_left.y));
+        let right = (top_right.y + 1..=bottom_right.y).map(move |y| Coord::new(top_right.x, y));
+        let bottom = (bottom_left.x..bottom_right.x)
+            .rev()
+            .filter(move |_| !single_row)
+            .map(move |x| Coord::new(x, bottom_left.y));
+        let left = (top_left.y + 1..bottom_left.y)
+            .rev()
+            .filter(move |_| !single_column)
+            .map(move |y| Coord::new(top_left.x, y));
+
+        top.chain(right)
+            .chain(bottom)
+            .chain(left)
+            .filter(move |_| !empty)
+    }
+
+    /// Iterates over all tiles of the area, row by row.
     pub fn iter(&self) -> AreaIter {
         AreaIter {
             pos: (0, 0).into(),
@@ -74,20 +211,15 @@ impl Iterator for AreaIter {
     type Item = Coord;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pos.x <= self.size.x && self.pos.y <= self.size.y {
-            if self.pos.x > self.size.x {
-                self.pos.x = 0;
-                self.pos.y += 1;
-            }
-            let ret = Some(self.pos + self.position);
-            self.pos.x += 1;
-            if self.pos.x > self.size.x {
-                self.pos.x = 0;
-                self.pos.y += 1;
-            }
-            ret
-        } else {
-            None
+        if self.size.x <= 0 || self.pos.y >= self.size.y {
+            return None;
+        }
+        let ret = self.pos + self.position;
+        self.pos.x += 1;
+        if self.pos.x >= self.size.x {
+            self.pos.x = 0;
+            self.pos.y += 1;
         }
+        Some(ret)
     }
 }
@@ -0,0 +1,296 @@
+use std::error::Error;
+use std::fmt::{self, Debug};
+use std::str::FromStr;
+
+use crate::{Coord, HexCoord, Map, OffsetLayout};
+
+type Transition<S> = Box<dyn Fn(&S, &[S]) -> S>;
+
+/// The cells which count as neighbours of a cell.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub enum Neighbourhood {
+    /// All cells within a square of the given radius.
+    Moore(isize),
+    /// All cells within the given number of orthogonal steps.
+    VonNeumann(isize),
+    /// All hexes within the given number of steps, the map is stored in the given layout.
+    Hex(OffsetLayout, isize),
+}
+impl Neighbourhood {
+    /// The positions of the neighbours of `pos`, not including `pos` itself.
+    pub fn around(&self, pos: Coord) -> Vec<Coord> {
+        let mut around = Vec::with_capacity(self.count());
+        self.around_into(pos, &mut around);
+        around
+    }
+
+    /// Like [Neighbourhood::around], but replaces the contents of `around` instead of allocating.
+    pub fn around_into(&self, pos: Coord, around: &mut Vec<Coord>) {
+        around.clear();
+        match *self {
+            Neighbourhood::Moore(r) => {
+                for y in -r..=r {
+                    for x in -r..=r {
+                        if x != 0 || y != 0 {
+                            around.push(pos + (x, y).into());
+                        }
+                    }
+                }
+            }
+            Neighbourhood::VonNeumann(r) => {
+                for y in -r..=r {
+                    let w = r - y.abs();
+                    for x in -w..=w {
+                        if x != 0 || y != 0 {
+                            around.push(pos + (x, y).into());
+                        }
+                    }
+                }
+            }
+            Neighbourhood::Hex(layout, r) => around.extend(
+                HexCoord::from_offset(pos, layout)
+                    .spiral()
+                    .skip(1)
+                    .take((3 * r * (r + 1)) as usize)
+                    .map(|h| h.to_offset(layout)),
+            ),
+        }
+    }
+
+    /// The number of neighbours of a cell.
+    fn count(&self) -> usize {
+        match *self {
+            Neighbourhood::Moore(r) => ((2 * r + 1) * (2 * r + 1) - 1) as usize,
+            Neighbourhood::VonNeumann(r) => (2 * r * (r + 1)) as usize,
+            Neighbourhood::Hex(_, r) => (3 * r * (r + 1)) as usize,
+        }
+    }
+}
+
+/// What neighbours outside of the map count as.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub enum Boundary<S> {
+    /// Every cell outside of the map is in the given state.
+    Wall(S),
+    /// The map wraps around, the left edge touches the right edge and the top the bottom.
+    ///
+    /// Hex maps need an even size along their shoved axis to wrap without seams.
+    Wrap,
+    /// The map is mirrored at its edges, so the cells next to an edge see themselves.
+    Mirror,
+}
+impl<S> Boundary<S> {
+    /// The local position within a map of `size` which `pos` stands for, or None when it hits a
+    /// wall.
+    fn resolve(&self, pos: Coord, size: Coord) -> Option<Coord> {
+        let mirror = |v: isize, len: isize| {
+            let m = v.rem_euclid(2 * len);
+            if m < len {
+                m
+            } else {
+                2 * len - 1 - m
+            }
+        };
+        let inside = pos.x >= 0 && pos.y >= 0 && pos.x < size.x && pos.y < size.y;
+        match self {
+            _ if inside => Some(pos),
+            Boundary::Wall(_) => None,
+            Boundary::Wrap => Some(Coord::new(
+                pos.x.rem_euclid(size.x),
+                pos.y.rem_euclid(size.y),
+            )),
+            Boundary::Mirror => Some(Coord::new(mirror(pos.x, size.x), mirror(pos.y, size.y))),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub enum ParseRuleError {
+    /// The rule has no `B` part.
+    MissingBirth,
+    /// The rule has no `S` part.
+    MissingSurvival,
+    /// A part which does not start with `B` or `S`, or which appears twice.
+    InvalidPart(String),
+    /// A neighbour count which is not a digit.
+    InvalidCount(char),
+}
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRuleError::MissingBirth => write!(f, "rule has no birth part"),
+            ParseRuleError::MissingSurvival => write!(f, "rule has no survival part"),
+            ParseRuleError::InvalidPart(part) => write!(f, "invalid rule part '{}'", part),
+            ParseRuleError::InvalidCount(c) => write!(f, "invalid neighbour count '{}'", c),
+        }
+    }
+}
+impl Error for ParseRuleError {}
+
+/// A Life-like rule, which decides between two states by the number of living neighbours.
+///
+/// Parsed from the B/S notation, `B3/S23` is Conway's Game of Life and `B5678/S45678` grows
+/// caves when walls are the living cells.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Default)]
+pub struct LifeRule {
+    /// The neighbour counts which bring a dead cell to life.
+    pub birth: Vec<usize>,
+    /// The neighbour counts which keep a living cell alive.
+    pub survival: Vec<usize>,
+}
+impl LifeRule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        LifeRule {
+            birth: birth.to_vec(),
+            survival: survival.to_vec(),
+        }
+    }
+
+    /// Whether a cell is alive in the next generation.
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survival.contains(&neighbours)
+        } else {
+            self.birth.contains(&neighbours)
+        }
+    }
+}
+impl FromStr for LifeRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut birth = None;
+        let mut survival = None;
+        for part in s.trim().split('/') {
+            let mut chars = part.chars();
+            let target = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') if birth.is_none() => &mut birth,
+                Some('S') if survival.is_none() => &mut survival,
+                _ => return Err(ParseRuleError::InvalidPart(part.to_string())),
+            };
+            let counts = chars
+                .map(|c| {
+                    c.to_digit(10)
+                        .map(|d| d as usize)
+                        .ok_or(ParseRuleError::InvalidCount(c))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            *target = Some(counts);
+        }
+        Ok(LifeRule {
+            birth: birth.ok_or(ParseRuleError::MissingBirth)?,
+            survival: survival.ok_or(ParseRuleError::MissingSurvival)?,
+        })
+    }
+}
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("B")?;
+        for n in &self.birth {
+            write!(f, "{}", n)?;
+        }
+        f.write_str("/S")?;
+        for n in &self.survival {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+/// A cellular automaton over the cells of a [Map].
+///
+/// Every generation each cell is replaced by the result of the transition, which receives the
+/// cell and the states of its neighbours in the order of [Neighbourhood::around]. All cells are
+/// updated at once from the previous generation.
+pub struct Automaton<S>
+where
+    S: Debug + Clone + PartialEq,
+{
+    pub neighbourhood: Neighbourhood,
+    pub boundary: Boundary<S>,
+    transition: Transition<S>,
+}
+impl<S> Automaton<S>
+where
+    S: Debug + Clone + PartialEq + 'static,
+{
+    /// Creates an automaton with a [Boundary::Mirror] boundary.
+    pub fn new<F>(neighbourhood: Neighbourhood, transition: F) -> Self
+    where
+        F: Fn(&S, &[S]) -> S + 'static,
+    {
+        Automaton {
+            neighbourhood,
+            boundary: Boundary::Mirror,
+            transition: Box::new(transition),
+        }
+    }
+
+    /// Creates an automaton following a Life-like rule, cells in any other state than `alive`
+    /// count as dead and die into `dead`.
+    pub fn life(rule: LifeRule, neighbourhood: Neighbourhood, alive: S, dead: S) -> Self {
+        Automaton::new(neighbourhood, move |cell, neighbours| {
+            let count = neighbours.iter().filter(|n| **n == alive).count();
+            if rule.next(*cell == alive, count) {
+                alive.clone()
+            } else {
+                dead.clone()
+            }
+        })
+    }
+
+    pub fn with_boundary(mut self, boundary: Boundary<S>) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// The states of the neighbours of the cell at the world position `pos`.
+    pub fn neighbours(&self, map: &Map<S>, pos: Coord) -> Vec<S> {
+        let mut states = Vec::with_capacity(self.neighbourhood.count());
+        self.neighbours_into(map, pos, &mut vec![], &mut states);
+        states
+    }
+
+    /// Replaces the contents of `states` with the states of the neighbours of `pos`, using
+    /// `around` as scratch space for their positions.
+    fn neighbours_into(
+        &self,
+        map: &Map<S>,
+        pos: Coord,
+        around: &mut Vec<Coord>,
+        states: &mut Vec<S>,
+    ) {
+        self.neighbourhood.around_into(map.to_local(pos), around);
+        states.clear();
+        states.extend(around.iter().map(|p| {
+            match (self.boundary.resolve(*p, map.area.size), &self.boundary) {
+                (Some(p), _) => map.get_local(p).unwrap().clone(),
+                (None, Boundary::Wall(wall)) => wall.clone(),
+                (None, _) => unreachable!(),
+            }
+        }));
+    }
+
+    /// Calculates the next generation.
+    pub fn step(&self, map: &Map<S>) -> Map<S> {
+        let (mut around, mut states) = (vec![], vec![]);
+        let mut data = Vec::with_capacity(map.data.len());
+        for (p, cell) in map.iter_ref() {
+            self.neighbours_into(map, p, &mut around, &mut states);
+            data.push((self.transition)(cell, &states));
+        }
+        Map {
+            area: map.area,
+            data,
+        }
+    }
+
+    /// Calculates the generation `steps` generations after `map`.
+    pub fn run(&self, map: &Map<S>, steps: usize) -> Map<S> {
+        let mut map = map.clone();
+        for _ in 0..steps {
+            map = self.step(&map);
+        }
+        map
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::Debug;
+use std::ops::{BitAnd, BitOr, Sub};
+
+use crate::{Area, Coord, Map, MapObject, MovementCost};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A map of booleans packed into 64 bit words, for layers like visible, explored or blocked.
+///
+/// Positions are world positions, like indexing a [Map]. Set operations between bitmaps work a
+/// word at a time and require both bitmaps to have the same [Area].
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct BitMap {
+    pub area: Area,
+    words: Vec<u64>,
+}
+impl BitMap {
+    /// Creates a bitmap with all bits cleared.
+    pub fn new(size: Coord) -> Self {
+        let bits = (size.x.max(0) * size.y.max(0)) as usize;
+        BitMap {
+            area: Area::new((0, 0).into(), size),
+            words: vec![0; bits.div_ceil(BITS)],
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Coord) -> Self {
+        self.area.position = offset;
+        self
+    }
+
+    fn index(&self, pos: Coord) -> Option<usize> {
+        if self.area.point_within(pos) {
+            let local = pos - self.area.position;
+            Some((local.y * self.area.size.x + local.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the bit at `pos` is set, positions outside of the bitmap are never set.
+    pub fn get(&self, pos: Coord) -> bool {
+        match self.index(pos) {
+            Some(i) => self.words[i / BITS] & (1 << (i % BITS)) != 0,
+            None => false,
+        }
+    }
+
+    /// Sets the bit at `pos`, panics when `pos` lies outside of the bitmap.
+    pub fn set(&mut self, pos: Coord, value: bool) {
+        let i = self
+            .index(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the bitmap {:?}", pos, self.area));
+        if value {
+            self.words[i / BITS] |= 1 << (i % BITS);
+        } else {
+            self.words[i / BITS] &= !(1 << (i % BITS));
+        }
+    }
+
+    /// Sets or clears all bits.
+    pub fn fill(&mut self, value: bool) {
+        let fill = if value { u64::MAX } else { 0 };
+        self.words.iter_mut().for_each(|w| *w = fill);
+        self.clear_unused_bits();
+    }
+
+    /// The number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns true when no bit is set.
+    pub fn is_clear(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Sets every bit which is set in `other`.
+    pub fn union_with(&mut self, other: &BitMap) {
+        self.combine_with(other, |a, b| a | b);
+    }
+
+    /// Clears every bit which is not set in `other`.
+    pub fn intersect_with(&mut self, other: &BitMap) {
+        self.combine_with(other, |a, b| a & b);
+    }
+
+    /// Clears every bit which is set in `other`.
+    pub fn difference_with(&mut self, other: &BitMap) {
+        self.combine_with(other, |a, b| a & !b);
+    }
+
+    fn combine_with<F>(&mut self, other: &BitMap, func: F)
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        assert!(self.area == other.area);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a = func(*a, *b);
+        }
+    }
+
+    /// The bits past the last tile of the last word must stay cleared for [BitMap::count_ones].
+    fn clear_unused_bits(&mut self) {
+        let bits = (self.area.size.x.max(0) * self.area.size.y.max(0)) as usize;
+        let unused = self.words.len() * BITS - bits;
+        if let Some(last) = self.words.last_mut() {
+            *last &= u64::MAX >> unused;
+        }
+    }
+
+    /// Iterates over the positions of all set bits, row by row.
+    pub fn iter(&self) -> BitMapIter<'_> {
+        BitMapIter {
+            map: self,
+            word: 0,
+            bits: self.words.first().copied().unwrap_or(0),
+        }
+    }
+}
+impl BitOr for &BitMap {
+    type Output = BitMap;
+
+    fn bitor(self, other: &BitMap) -> BitMap {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+}
+impl BitAnd for &BitMap {
+    type Output = BitMap;
+
+    fn bitand(self, other: &BitMap) -> BitMap {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+}
+impl Sub for &BitMap {
+    type Output = BitMap;
+
+    fn sub(self, other: &BitMap) -> BitMap {
+        let mut result = self.clone();
+        result.difference_with(other);
+        result
+    }
+}
+
+/// Iterator over the positions of the set bits of a [BitMap].
+#[derive(Debug, Clone)]
+pub struct BitMapIter<'a> {
+    map: &'a BitMap,
+    word: usize,
+    bits: u64,
+}
+impl Iterator for BitMapIter<'_> {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bits == 0 {
+            self.word += 1;
+            self.bits = *self.map.words.get(self.word)?;
+        }
+        let i = self.word * BITS + self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+
+        let width = self.map.area.size.x as usize;
+        Some(self.map.area.position + Coord::new((i % width) as isize, (i / width) as isize))
+    }
+}
+
+impl<T> Map<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    /// Creates a bitmap with the same area, setting the bits of the tiles matching `predicate`.
+    pub fn to_bitmap<F>(&self, predicate: F) -> BitMap
+    where
+        F: Fn(Coord, &T) -> bool,
+    {
+        let mut bitmap = BitMap::new(self.area.size).with_offset(self.area.position);
+        for y in 0..self.area.size.y {
+            for x in 0..self.area.size.x {
+                let p = self.to_world((x, y).into());
+                if predicate(p, &self[p]) {
+                    bitmap.set(p, true);
+                }
+            }
+        }
+        bitmap
+    }
+}
+
+impl<T> Map<T>
+where
+    T: MapObject,
+{
+    /// The tiles which can be seen through.
+    pub fn transparent_bitmap(&self) -> BitMap {
+        self.to_bitmap(|_, t| t.is_transparent())
+    }
+
+    /// The tiles which can be walked on.
+    pub fn walkable_bitmap(&self) -> BitMap {
+        self.to_bitmap(|_, t| t.is_walkable() != MovementCost::Impossible)
+    }
+}
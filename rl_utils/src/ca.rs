@@ -1,4 +1,6 @@
 use crate::{Area, Coord, Map, MapObject, MovementCost};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::fmt;
 
 #[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
@@ -62,7 +64,7 @@ impl CA {
             min.y = 0;
         }
 
-        let area = Area::new(min, (radius * 2, radius * 2).into());
+        let area = Area::new(min, (radius * 2 + 1, radius * 2 + 1).into());
         area.iter().for_each(|p| {
             if let Some(ca) = map.get(p) {
                 if ca.tile == CATile::Dead {
@@ -76,11 +78,7 @@ impl CA {
         let sum_r1 = Self::calc_wall_sum(pos, 1, map);
         let sum_r2 = Self::calc_wall_sum(pos, 2, map);
 
-        self.next = if sum_r1 >= params.r1 || sum_r2 <= params.r2 {
-            CATile::Dead
-        } else {
-            CATile::Alive
-        };
+        self.next = params.rule(sum_r1, sum_r2);
 
         self
     }
@@ -115,14 +113,95 @@ pub struct CAparams {
     pub r2: usize,
 }
 
+impl CAparams {
+    /// The next state of a cell from the number of walls in its windows of radius 1 and 2.
+    fn rule(&self, sum_r1: usize, sum_r2: usize) -> CATile {
+        if sum_r1 >= self.r1 || sum_r2 <= self.r2 {
+            CATile::Dead
+        } else {
+            CATile::Alive
+        }
+    }
+}
+
+/// A summed-area table of the walls of a map, which counts the walls within any rectangle in
+/// constant time.
+struct WallSums {
+    position: Coord,
+    size: Coord,
+    sums: Vec<u32>,
+}
+impl WallSums {
+    fn new(map: &Map<CA>) -> Self {
+        let Coord { x: w, y: h } = map.area.size;
+        let stride = (w + 1) as usize;
+        let mut sums = vec![0; stride * (h + 1) as usize];
+        for y in 0..h as usize {
+            let mut row = 0;
+            for x in 0..w as usize {
+                if map.data[y * w as usize + x].tile == CATile::Dead {
+                    row += 1;
+                }
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row;
+            }
+        }
+        WallSums {
+            position: map.area.position,
+            size: map.area.size,
+            sums,
+        }
+    }
+
+    /// The number of walls within the local rectangle from `min` up to `max`, clipped to the map.
+    fn count(&self, min: Coord, max: Coord) -> usize {
+        let (x0, y0) = (min.x.clamp(0, self.size.x), min.y.clamp(0, self.size.y));
+        let (x1, y1) = (max.x.clamp(0, self.size.x), max.y.clamp(0, self.size.y));
+        if x0 >= x1 || y0 >= y1 {
+            return 0;
+        }
+        let stride = self.size.x + 1;
+        let at = |x: isize, y: isize| self.sums[(y * stride + x) as usize];
+        (at(x1, y1) + at(x0, y0) - at(x0, y1) - at(x1, y0)) as usize
+    }
+
+    /// The same count as [CA::calc_wall_sum].
+    fn window(&self, pos: Coord, radius: isize) -> usize {
+        let min = Coord::new((pos.x - radius).max(0), (pos.y - radius).max(0)) - self.position;
+        self.count(min, min + (radius * 2 + 1, radius * 2 + 1).into())
+    }
+}
+
+/// Runs one generation of the fixed cave rule of [CA::check] over the cells from (0,0) up to
+/// `size`, see [crate::Automaton] for other rules and neighbourhoods.
+///
+/// The walls around every cell are counted with a summed-area table. With the `parallel` feature
+/// the rows are calculated on the rayon thread pool, which gives exactly the same result.
 pub fn ca_generate(size: Coord, param: &CAparams, cmap: &mut Map<CA>) {
-    let area = Area::new((0, 0).into(), size);
-    area.iter().for_each(|p| {
-        let ca = cmap[p];
-        cmap[p] = ca.check(p, param, &cmap);
-    });
-    area.iter().for_each(|p| {
-        let ca = cmap[p];
-        cmap[p] = ca.update();
-    });
+    if size.x <= 0 || size.y <= 0 {
+        return;
+    }
+
+    // All cells are calculated from the walls of the previous generation, so the rows of the
+    // next generation can be filled in any order.
+    let sums = WallSums::new(cmap);
+    let mut next = vec![CATile::Dead; (size.x * size.y) as usize];
+    let fill_row = |(y, row): (usize, &mut [CATile])| {
+        for (x, tile) in row.iter_mut().enumerate() {
+            let p = Coord::new(x as isize, y as isize);
+            *tile = param.rule(sums.window(p, 1), sums.window(p, 2));
+        }
+    };
+    #[cfg(feature = "parallel")]
+    next.par_chunks_mut(size.x as usize)
+        .enumerate()
+        .for_each(fill_row);
+    #[cfg(not(feature = "parallel"))]
+    next.chunks_mut(size.x as usize)
+        .enumerate()
+        .for_each(fill_row);
+
+    for (i, tile) in next.into_iter().enumerate() {
+        let p = Coord::new(i as isize % size.x, i as isize / size.x);
+        cmap[p] = CA { tile, next: tile };
+    }
 }
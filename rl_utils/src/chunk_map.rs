@@ -0,0 +1,292 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+
+use crate::path::astar_by;
+use crate::{Area, Coord, Map, MapMovement, MapObject, MovementCost, Path, PathConfig};
+
+type Generator<T> = Box<dyn FnMut(Coord) -> Map<T>>;
+type Sink<T> = Box<dyn FnMut(Coord, Map<T>)>;
+
+struct Chunk<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    map: Map<T>,
+    last_used: Cell<u64>,
+}
+
+/// An unbounded map made of equally sized chunks, for worlds too large to keep in memory.
+///
+/// Chunks are addressed by their chunk position, chunk (0,0) covers the tiles from (0,0) up to
+/// the chunk size, chunk (-1,0) the tiles left of it. A chunk is created by the generator the
+/// first time it is loaded. When more than the maximum number of chunks are loaded, the least
+/// recently used chunks are handed to the sink, which may for example save them to disk.
+///
+/// [ChunkMap::astar] searches across chunk borders directly. For the other path finders, the
+/// field of view and the analysis functions of [Map], copy the area of interest with
+/// [ChunkMap::to_map].
+pub struct ChunkMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    chunk_size: Coord,
+    max_loaded: usize,
+    chunks: BTreeMap<Coord, Chunk<T>>,
+    tick: Cell<u64>,
+    generator: Generator<T>,
+    sink: Sink<T>,
+}
+impl<T> ChunkMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    /// Creates an empty chunk map, `generator` creates the contents of a chunk from its chunk
+    /// position and must return a map of `chunk_size`.
+    pub fn new<F>(chunk_size: Coord, generator: F) -> Self
+    where
+        F: FnMut(Coord) -> Map<T> + 'static,
+    {
+        assert!(chunk_size.x > 0 && chunk_size.y > 0);
+        ChunkMap {
+            chunk_size,
+            max_loaded: usize::MAX,
+            chunks: BTreeMap::new(),
+            tick: Cell::new(0),
+            generator: Box::new(generator),
+            sink: Box::new(|_, _| {}),
+        }
+    }
+
+    /// Limits the number of loaded chunks, by default there is no limit.
+    pub fn with_max_loaded(mut self, max_loaded: usize) -> Self {
+        assert!(max_loaded > 0);
+        self.max_loaded = max_loaded;
+        self
+    }
+
+    /// Sets where unloaded chunks go to, by default they are dropped.
+    pub fn with_sink<F>(mut self, sink: F) -> Self
+    where
+        F: FnMut(Coord, Map<T>) + 'static,
+    {
+        self.sink = Box::new(sink);
+        self
+    }
+
+    pub fn chunk_size(&self) -> Coord {
+        self.chunk_size
+    }
+
+    /// The position of the chunk containing the world position `pos`.
+    pub fn chunk_of(&self, pos: Coord) -> Coord {
+        Coord::new(
+            pos.x.div_euclid(self.chunk_size.x),
+            pos.y.div_euclid(self.chunk_size.y),
+        )
+    }
+
+    /// The tiles covered by `chunk` in world positions.
+    pub fn chunk_area(&self, chunk: Coord) -> Area {
+        Area::new(
+            Coord::new(chunk.x * self.chunk_size.x, chunk.y * self.chunk_size.y),
+            self.chunk_size,
+        )
+    }
+
+    pub fn is_loaded(&self, chunk: Coord) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    /// The positions of all loaded chunks.
+    pub fn loaded_chunks(&self) -> Vec<Coord> {
+        self.chunks.keys().copied().collect()
+    }
+
+    /// Returns the loaded chunk, or None when it is not loaded.
+    pub fn chunk(&self, chunk: Coord) -> Option<&Map<T>> {
+        self.chunks.get(&chunk).map(|c| {
+            self.touch(c);
+            &c.map
+        })
+    }
+
+    /// Returns the chunk, creating it with the generator when it is not loaded.
+    ///
+    /// Loading a chunk may unload the least recently used other chunk.
+    pub fn load(&mut self, chunk: Coord) -> &mut Map<T> {
+        if !self.chunks.contains_key(&chunk) {
+            let area = self.chunk_area(chunk);
+            let map = (self.generator)(chunk).with_offset(area.position);
+            assert!(map.area == area);
+
+            self.chunks.insert(
+                chunk,
+                Chunk {
+                    map,
+                    last_used: Cell::new(0),
+                },
+            );
+            while self.chunks.len() > self.max_loaded {
+                let oldest = self
+                    .chunks
+                    .iter()
+                    .filter(|(c, _)| **c != chunk)
+                    .min_by_key(|(_, loaded)| loaded.last_used.get())
+                    .map(|(c, _)| *c)
+                    .unwrap();
+                self.unload(oldest);
+            }
+        }
+
+        let loaded = &self.chunks[&chunk];
+        self.touch(loaded);
+        &mut self.chunks.get_mut(&chunk).unwrap().map
+    }
+
+    /// Loads every chunk overlapping `area`.
+    pub fn load_area(&mut self, area: Area) {
+        if area.size.x <= 0 || area.size.y <= 0 {
+            return;
+        }
+        let first = self.chunk_of(area.position);
+        let last = self.chunk_of(area.position + area.size - (1, 1).into());
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                self.load((x, y).into());
+            }
+        }
+    }
+
+    /// Hands the chunk to the sink, returns false when it was not loaded.
+    pub fn unload(&mut self, chunk: Coord) -> bool {
+        match self.chunks.remove(&chunk) {
+            Some(loaded) => {
+                (self.sink)(chunk, loaded.map);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Hands all loaded chunks to the sink, for example before saving the game.
+    pub fn unload_all(&mut self) {
+        for chunk in self.loaded_chunks() {
+            self.unload(chunk);
+        }
+    }
+
+    /// Returns the tile at the world position `pos`, or None when its chunk is not loaded.
+    pub fn get(&self, pos: Coord) -> Option<&T> {
+        self.chunk(self.chunk_of(pos)).and_then(|map| map.get(pos))
+    }
+
+    /// Mutable version of [ChunkMap::get].
+    pub fn get_mut(&mut self, pos: Coord) -> Option<&mut T> {
+        let chunk = self.chunk_of(pos);
+        let loaded = self.chunks.get(&chunk)?;
+        self.touch(loaded);
+        self.chunks.get_mut(&chunk)?.map.get_mut(pos)
+    }
+
+    /// Returns the tile at the world position `pos`, loading its chunk when needed.
+    pub fn get_or_load(&mut self, pos: Coord) -> &mut T {
+        let chunk = self.chunk_of(pos);
+        &mut self.load(chunk)[pos]
+    }
+
+    /// Iterates over the tiles of all loaded chunks, chunk by chunk.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, T)> + '_ {
+        self.chunks.values().flat_map(|c| c.map.iter())
+    }
+
+    /// Copies `area` into a single map, loading the chunks it overlaps.
+    ///
+    /// The returned map has `area.position` as offset, so the path finding, field of view and
+    /// analysis functions of [Map] can be used across chunk borders with world positions.
+    pub fn to_map(&mut self, area: Area) -> Map<T> {
+        if area.size.x <= 0 || area.size.y <= 0 {
+            return Map::new(area.size).with_offset(area.position);
+        }
+        let mut map: Map<Option<T>> = Map::new(area.size).with_offset(area.position);
+        map.fill(None);
+
+        let first = self.chunk_of(area.position);
+        let last = self.chunk_of(area.position + area.size - (1, 1).into());
+        for cy in first.y..=last.y {
+            for cx in first.x..=last.x {
+                // Copy every chunk right after loading it, so a small maximum of loaded chunks
+                // does not unload parts of the area before they are copied.
+                let chunk = self.load((cx, cy).into());
+                for y in 0..chunk.area.size.y {
+                    for x in 0..chunk.area.size.x {
+                        let p = chunk.to_world((x, y).into());
+                        if area.point_within(p) {
+                            map[p] = Some(chunk[p].clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut result = Map::new(area.size).with_offset(area.position);
+        result.data = map.data.into_iter().flatten().collect();
+        result
+    }
+
+    fn touch(&self, chunk: &Chunk<T>) {
+        let tick = self.tick.get() + 1;
+        self.tick.set(tick);
+        chunk.last_used.set(tick);
+    }
+}
+
+impl<T> ChunkMap<T>
+where
+    T: MapObject,
+{
+    /// Like [Map::walkable_tiles], tiles in chunks which are not loaded are not walkable.
+    pub fn walkable_tiles(&self, pos: Coord, movement: MapMovement) -> Vec<(Coord, usize)> {
+        let mut retvec = vec![];
+        for i in movement.get_reachable_tiles() {
+            if let Some(c) = self.get(pos + i) {
+                match c.is_walkable() {
+                    MovementCost::Possible(cost) => {
+                        retvec.push((pos + i, cost));
+                    }
+                    MovementCost::Impossible => (),
+                }
+            }
+        }
+        retvec
+    }
+
+    /// Finds the cheapest path from `start` to `goal` like [Map::astar], across the borders of
+    /// the loaded chunks.
+    ///
+    /// Chunks are not loaded during the search, so load the chunks the path may lead through
+    /// first, for example with [ChunkMap::load_area].
+    pub fn astar(&self, start: Coord, goal: Coord, config: &PathConfig) -> Option<Path> {
+        let neighbours = |current: Coord| {
+            self.walkable_tiles(current, config.movement)
+                .into_iter()
+                .map(|(neighbour, step)| (neighbour, config.step_cost(current, neighbour, step)))
+                .collect()
+        };
+        let heuristic = |pos: Coord| config.distance.estimate(pos, goal);
+        astar_by(start, goal, neighbours, heuristic, config.max_cost)
+    }
+}
+
+impl<T> Debug for ChunkMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ChunkMap")
+            .field("chunk_size", &self.chunk_size)
+            .field("max_loaded", &self.max_loaded)
+            .field("loaded_chunks", &self.loaded_chunks())
+            .finish()
+    }
+}
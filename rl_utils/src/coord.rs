@@ -3,7 +3,7 @@ use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 use min_max_macros::max;
 
-use crate::tranthong;
+use crate::{tranthong, Distance};
 
 #[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
 pub struct Coord {
@@ -24,6 +24,7 @@ impl Coord {
         a.y = (self.y - b.y).abs();
         a
     }
+    /// The Manhattan distance, see [Distance].
     pub fn delta_abs_total(self, b: Coord) -> isize {
         let t = self.delta_abs(b);
         t.x + t.y
@@ -34,12 +35,17 @@ impl Coord {
     pub fn is_neightbour(self, b: Coord) -> bool {
         (self.x - b.x).abs() <= 1 && (self.y - b.y).abs() <= 1
     }
+    /// The Chebyshev distance, see [Distance].
     pub fn pyth(self, end: Coord) -> isize {
         max!((self.x - end.x).abs(), (self.y - end.y).abs())
     }
+    /// The Euclidean distance, see [Distance].
     pub fn real_pyth(self, end: Coord) -> f64 {
         (((self.x - end.x).pow(2) + (self.y - end.y).pow(2)) as f64).sqrt()
     }
+    pub fn distance(self, end: Coord, metric: Distance) -> f64 {
+        metric.measure(self, end)
+    }
     pub fn line(self, end: Coord) -> Vec<Coord> {
         tranthong(self, end)
     }
@@ -1,10 +1,11 @@
 use min_max_macros::max;
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter, Result};
 use std::vec::Vec;
 use yansi::Paint;
 
-use crate::{Coord, Map, MapMovement, MapObject, MovementCost};
+use crate::{Coord, Distance, Map, MapMovement, MapObject, MovementCost};
 
 pub fn rgb(minimum: isize, maximum: isize, value: isize) -> (u8, u8, u8) {
     let min = minimum as f32;
@@ -65,18 +66,34 @@ pub struct DijkstraMap {
     pub map: Map<DijkstraMapValue>,
     goals: Vec<Coord>,
     avoid: Vec<Coord>,
+    movement: MapMovement,
+    distance: Distance,
+    diagonal_cost: Option<f32>,
+    costs: Option<Map<MovementCost>>,
+    /// The unrounded costs while a diagonal multiplier is set, in [DijkstraMap::FIXED_POINT].
+    exact: Option<Map<isize>>,
 }
 impl DijkstraMap {
+    /// The scale costs are accumulated in while a diagonal multiplier is set.
+    const FIXED_POINT: isize = 100;
     pub fn new(size: Coord) -> Self {
         DijkstraMap {
             map: Map::new(size),
             goals: vec![],
             avoid: vec![],
+            movement: MapMovement::Both,
+            distance: Distance::Chebyshev,
+            diagonal_cost: None,
+            costs: None,
+            exact: None,
         }
         .blank()
     }
     pub fn with_offset(mut self, offset: Coord) -> Self {
         self.map.area.position = offset;
+        if let Some(costs) = &mut self.costs {
+            costs.area.position = offset;
+        }
         self
     }
     pub fn blank(mut self) -> Self {
@@ -102,6 +119,164 @@ impl DijkstraMap {
         self.goals.push(c);
         self
     }
+    /// Sets which neighbours are reachable from a tile, defaults to [MapMovement::Both].
+    pub fn with_movement(mut self, movement: MapMovement) -> Self {
+        self.movement = movement;
+        self
+    }
+    /// Sets how steps are weighed, see [Distance::step_weights], defaults to
+    /// [Distance::Chebyshev].
+    ///
+    /// Only used by [DijkstraMap::calculate_weighted], whose costs are then measured in the units
+    /// of the step weights.
+    pub fn with_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+    /// Sets the multiplier applied to the weight of a diagonal step.
+    ///
+    /// Only used by [DijkstraMap::calculate_weighted]. Costs stay measured in the units of the
+    /// step weights, they are accumulated unrounded and only rounded when stored in the map, so
+    /// multipliers such as 1.41 are not rounded away on cheap tiles.
+    pub fn with_diagonal_cost(mut self, multiplier: f32) -> Self {
+        self.diagonal_cost = Some(multiplier);
+        self
+    }
+    /// Uses the [MovementCost] of each tile in `terrain` as the cost of entering that tile.
+    ///
+    /// Tiles which are not walkable are marked as [DijkstraMapValue::Impassable], unless they
+    /// are a goal or should be avoided. Only used by [DijkstraMap::calculate_weighted].
+    pub fn with_movement_costs<T>(mut self, terrain: &Map<T>) -> Self
+    where
+        T: MapObject,
+    {
+        assert!(self.map.area == terrain.area);
+
+        let costs = Map {
+            area: terrain.area,
+            data: terrain.data.iter().map(|t| t.is_walkable()).collect(),
+        };
+        for y in 0..self.map.area.size.y {
+            for x in 0..self.map.area.size.x {
+                let p = self.map.to_world((x, y).into());
+                if let MovementCost::Impossible = costs[p] {
+                    match self.map[p] {
+                        DijkstraMapValue::Default | DijkstraMapValue::NonGoal(_) => {
+                            self.map[p] = DijkstraMapValue::Impassable
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        self.costs = Some(costs);
+        self
+    }
+    pub fn movement(&self) -> MapMovement {
+        self.movement
+    }
+    fn step_cost(&self, from: Coord, to: Coord) -> Option<isize> {
+        let cost = match &self.costs {
+            Some(costs) => match costs[to] {
+                MovementCost::Possible(cost) => cost as isize,
+                MovementCost::Impossible => return None,
+            },
+            None => 1,
+        };
+        let (orthogonal, diagonal) = self.step_weights();
+        if from.x != to.x && from.y != to.y {
+            Some(cost * diagonal)
+        } else {
+            Some(cost * orthogonal)
+        }
+    }
+    /// The weights of an orthogonal and a diagonal step, with the diagonal multiplier applied.
+    ///
+    /// Scaled by [DijkstraMap::scale], like the costs returned by [DijkstraMap::value].
+    fn step_weights(&self) -> (isize, isize) {
+        let (orthogonal, diagonal) = self.distance.step_weights();
+        match self.diagonal_cost {
+            Some(multiplier) => (
+                orthogonal as isize * Self::FIXED_POINT,
+                (diagonal as f32 * multiplier * Self::FIXED_POINT as f32).round() as isize,
+            ),
+            None => (orthogonal as isize, diagonal as isize),
+        }
+    }
+    /// How many internal cost units make up one unit of the values in the map.
+    fn scale(&self) -> isize {
+        match self.diagonal_cost {
+            Some(_) => Self::FIXED_POINT,
+            None => 1,
+        }
+    }
+    /// The cost of `p` as used while relaxing, unrounded while a diagonal multiplier is set.
+    ///
+    /// Values which were changed in the map since they were relaxed are taken from the map.
+    fn value(&self, p: Coord) -> isize {
+        match (self.map[p], &self.exact) {
+            (DijkstraMapValue::NonGoal(v), Some(exact)) if self.round(exact[p]) == v => exact[p],
+            (DijkstraMapValue::NonGoal(v), _) => v * self.scale(),
+            (value, _) => value.to_value(),
+        }
+    }
+    fn set_value(&mut self, p: Coord, value: isize) {
+        self.map[p] = DijkstraMapValue::NonGoal(self.round(value));
+        if self.scale() != 1 {
+            let area = self.map.area;
+            let exact = self.exact.get_or_insert_with(|| {
+                let mut exact = Map::new(area.size).with_offset(area.position);
+                exact.fill(0);
+                exact
+            });
+            exact[p] = value;
+        }
+    }
+    /// Rounds an internal cost to the units of the map, halves rounding up.
+    fn round(&self, value: isize) -> isize {
+        (value + self.scale() / 2).div_euclid(self.scale())
+    }
+    fn relax(&mut self, mut queue: BinaryHeap<Reverse<(isize, Coord)>>) {
+        while let Some(Reverse((cost, current))) = queue.pop() {
+            match self.map[current] {
+                DijkstraMapValue::NonGoal(_) | DijkstraMapValue::Goal => {
+                    if cost > self.value(current) {
+                        continue;
+                    }
+                    for (neighbour, _) in self.map.walkable_tiles(current, self.movement) {
+                        match self.map[neighbour] {
+                            DijkstraMapValue::Default | DijkstraMapValue::NonGoal(_) => {
+                                if let Some(step) = self.step_cost(current, neighbour) {
+                                    let next = cost + step;
+                                    if next < self.value(neighbour) {
+                                        self.set_value(neighbour, next);
+                                        queue.push(Reverse((next, neighbour)));
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    /// Calculates the true cheapest cost towards the nearest goal for every tile.
+    ///
+    /// Unlike [DijkstraMap::calculate], which adds 1 for every step, this uses the tile costs
+    /// given with [DijkstraMap::with_movement_costs], the step weights of
+    /// [DijkstraMap::with_distance] and the multiplier of [DijkstraMap::with_diagonal_cost].
+    pub fn calculate_weighted(mut self) -> Self {
+        let mut queue = BinaryHeap::new();
+
+        for g in &self.goals {
+            queue.push(Reverse((self.value(*g), *g)));
+        }
+        self.relax(queue);
+
+        self
+    }
     pub fn calculate(mut self) -> Self {
         let mut queue = VecDeque::new();
 
@@ -114,7 +289,7 @@ impl DijkstraMap {
                 let cost = self.map[current].to_value();
                 match self.map[current] {
                     DijkstraMapValue::NonGoal(_) | DijkstraMapValue::Goal => {
-                        for (neighbour, _) in self.map.walkable_tiles(current, MapMovement::Both) {
+                        for (neighbour, _) in self.map.walkable_tiles(current, self.movement) {
                             match self.map[neighbour] {
                                 DijkstraMapValue::Default | DijkstraMapValue::NonGoal(_) => {
                                     if cost + 1 < self.map[neighbour].to_value() {
@@ -138,7 +313,7 @@ impl DijkstraMap {
 
         for y in 0..self.map.area.size.y {
             for x in 0..self.map.area.size.x {
-                let p: Coord = (x, y).into();
+                let p = self.map.to_world((x, y).into());
                 match other.map[p] {
                     DijkstraMapValue::Default => {}
                     DijkstraMapValue::Impassable => {}
@@ -196,7 +371,7 @@ impl DijkstraMap {
         //for p in self.map.area.iter() {
         for y in 0..self.map.area.size.y {
             for x in 0..self.map.area.size.x {
-                let p: Coord = (x, y).into();
+                let p = self.map.to_world((x, y).into());
                 match self.map[p] {
                     DijkstraMapValue::Default => {}
                     DijkstraMapValue::Impassable => {}
@@ -217,13 +392,255 @@ impl DijkstraMap {
     pub fn invert(self) -> Self {
         self.invert_with_marge(0)
     }
+    /// Collects `from` and every tile whose cost may have been derived from it.
+    fn invalidate(&self, from: Coord) -> Vec<Coord> {
+        let mut affected = vec![from];
+        let mut seen = HashSet::new();
+        seen.insert(from);
+
+        let mut i = 0;
+        while i < affected.len() {
+            let current = affected[i];
+            i += 1;
+
+            let cost = match self.map[current] {
+                DijkstraMapValue::NonGoal(_) | DijkstraMapValue::Goal => self.value(current),
+                _ => continue,
+            };
+            for (neighbour, _) in self.map.walkable_tiles(current, self.movement) {
+                if let DijkstraMapValue::NonGoal(_) = self.map[neighbour] {
+                    let value = self.value(neighbour);
+                    if self.step_cost(current, neighbour) == Some(value - cost)
+                        && seen.insert(neighbour)
+                    {
+                        affected.push(neighbour);
+                    }
+                }
+            }
+        }
+        affected
+    }
+    /// Resets the affected tiles and relaxes them again from their unaffected neighbours.
+    fn repair(&mut self, affected: &[Coord]) {
+        let mut queue = BinaryHeap::new();
+
+        for p in affected {
+            if let DijkstraMapValue::NonGoal(_) = self.map[p] {
+                self.map[p] = DijkstraMapValue::Default;
+            }
+        }
+        for p in affected {
+            if let DijkstraMapValue::Goal = self.map[p] {
+                queue.push(Reverse((0, *p)));
+            }
+            for (neighbour, _) in self.map.walkable_tiles(*p, self.movement) {
+                match self.map[neighbour] {
+                    DijkstraMapValue::NonGoal(_) | DijkstraMapValue::Goal => {
+                        queue.push(Reverse((self.value(neighbour), neighbour)))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.relax(queue);
+    }
+    /// Adds a goal to a calculated map, only updating the tiles which get closer to a goal.
+    ///
+    /// The incremental updates result in the same map as [DijkstraMap::calculate_weighted].
+    pub fn add_goal(&mut self, c: Coord) {
+        if let DijkstraMapValue::Goal = self.map[c] {
+            return;
+        }
+        self.avoid.retain(|a| *a != c);
+        self.map[c] = DijkstraMapValue::Goal;
+        self.goals.push(c);
+
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((0, c)));
+        self.relax(queue);
+    }
+    /// Removes a goal from a calculated map, only updating the tiles which depended on it.
+    pub fn remove_goal(&mut self, c: Coord) {
+        if self.map[c] != DijkstraMapValue::Goal {
+            return;
+        }
+        let affected = self.invalidate(c);
+
+        self.goals.retain(|g| *g != c);
+        self.map[c] = match self.costs.as_ref().map(|costs| costs[c]) {
+            Some(MovementCost::Impossible) => DijkstraMapValue::Impassable,
+            _ => DijkstraMapValue::Default,
+        };
+        self.repair(&affected);
+    }
+    /// Marks a tile of a calculated map as impassable and updates the tiles which depended on it.
+    pub fn set_impassable(&mut self, c: Coord) {
+        let affected = self.invalidate(c);
+
+        self.goals.retain(|g| *g != c);
+        self.avoid.retain(|a| *a != c);
+        self.map[c] = DijkstraMapValue::Impassable;
+        if let Some(costs) = &mut self.costs {
+            costs[c] = MovementCost::Impossible;
+        }
+        self.repair(&affected);
+    }
+    /// Marks a tile of a calculated map as passable and updates the tiles which depend on it.
+    ///
+    /// The `cost` is only used when the map was created with [DijkstraMap::with_movement_costs].
+    pub fn set_passable(&mut self, c: Coord, cost: usize) {
+        let affected = self.invalidate(c);
+
+        if let DijkstraMapValue::Impassable = self.map[c] {
+            self.map[c] = DijkstraMapValue::Default;
+        }
+        if let Some(costs) = &mut self.costs {
+            costs[c] = MovementCost::Possible(cost);
+        }
+        self.repair(&affected);
+    }
+    /// Returns the neighbour of `from` with the lowest value, if that is lower than `from` itself.
+    ///
+    /// Only neighbours reachable with the [MapMovement] of this map are considered. Ties are
+    /// broken in the order of [MapMovement::get_reachable_tiles], which puts orthogonal steps
+    /// before diagonal ones.
+    pub fn next_step(&self, from: Coord) -> Option<Coord> {
+        let mut best = match self.map.get(from) {
+            Some(DijkstraMapValue::Goal) => return None,
+            Some(_) => self.value(from),
+            None => return None,
+        };
+        let mut step = None;
+
+        for (neighbour, _) in self.map.walkable_tiles(from, self.movement) {
+            match self.map[neighbour] {
+                DijkstraMapValue::Goal | DijkstraMapValue::NonGoal(_) => {
+                    let value = self.value(neighbour);
+                    if value < best {
+                        best = value;
+                        step = Some(neighbour);
+                    }
+                }
+                _ => {}
+            }
+        }
+        step
+    }
+    /// Follows the map downhill from `from` until a goal or a local minimum is reached.
+    ///
+    /// The returned path does not include `from`, and is empty when no step can be made.
+    pub fn downhill_path(&self, from: Coord) -> Vec<Coord> {
+        let mut path = vec![];
+        let mut current = from;
+
+        while let Some(next) = self.next_step(current) {
+            path.push(next);
+            current = next;
+        }
+        path
+    }
+    /// Returns all tiles which can reach a goal for at most `budget`.
+    pub fn within_cost(&self, budget: isize) -> Vec<Coord> {
+        let mut tiles = vec![];
+
+        for y in 0..self.map.area.size.y {
+            for x in 0..self.map.area.size.x {
+                let p = self.map.to_world((x, y).into());
+                match self.map[p] {
+                    DijkstraMapValue::Goal if budget >= 0 => tiles.push(p),
+                    DijkstraMapValue::NonGoal(cost) if cost <= budget => tiles.push(p),
+                    _ => {}
+                }
+            }
+        }
+        tiles
+    }
+    /// Combines two calculated maps, keeping the cost towards the nearest goal of either map.
+    pub fn merge_nearest(mut self, other: &DijkstraMap) -> Self {
+        assert!(self.map.area == other.map.area);
+
+        for y in 0..self.map.area.size.y {
+            for x in 0..self.map.area.size.x {
+                let p = self.map.to_world((x, y).into());
+                match other.map[p] {
+                    DijkstraMapValue::Default => {}
+                    DijkstraMapValue::Impassable => {}
+                    DijkstraMapValue::Goal => match self.map[p] {
+                        DijkstraMapValue::Avoid => {}
+                        DijkstraMapValue::Goal => {}
+                        _ => {
+                            self.map[p] = DijkstraMapValue::Goal;
+                            self.goals.push(p);
+                        }
+                    },
+                    DijkstraMapValue::Avoid => match self.map[p] {
+                        DijkstraMapValue::Avoid => {}
+                        _ => {
+                            self.map[p] = DijkstraMapValue::Avoid;
+                            self.avoid.push(p);
+                        }
+                    },
+                    DijkstraMapValue::NonGoal(ocost) => match self.map[p] {
+                        DijkstraMapValue::Default => self.map[p] = DijkstraMapValue::NonGoal(ocost),
+                        DijkstraMapValue::NonGoal(scost) if ocost < scost => {
+                            self.map[p] = DijkstraMapValue::NonGoal(ocost)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        self.goals
+            .retain(|g| other.map[g] != DijkstraMapValue::Avoid);
+        self
+    }
+    /// Turns a calculated approach map into a flee map, Brogue style.
+    ///
+    /// Every cost is multiplied by `coefficient`, which should be negative (e.g. -1.2), after
+    /// which the map is relaxed again. The tiles furthest from any goal end up being the lowest,
+    /// and since values flow around corners, following the map downhill no longer leads into
+    /// dead ends. The former goals become ordinary tiles.
+    pub fn safety(mut self, coefficient: f32) -> Self {
+        let mut queue = BinaryHeap::new();
+
+        for y in 0..self.map.area.size.y {
+            for x in 0..self.map.area.size.x {
+                let p = self.map.to_world((x, y).into());
+                let cost = match self.map[p] {
+                    DijkstraMapValue::Goal => 0,
+                    DijkstraMapValue::NonGoal(_) => {
+                        (self.value(p) as f32 * coefficient).round() as isize
+                    }
+                    _ => continue,
+                };
+                self.set_value(p, cost);
+                queue.push(Reverse((cost, p)));
+            }
+        }
+        self.goals.clear();
+        self.relax(queue);
+
+        self
+    }
+    /// Builds a flee map which avoids all given threat maps, see [DijkstraMap::safety].
+    ///
+    /// The threat maps should be calculated and share the same area.
+    pub fn safety_from(threats: &[&DijkstraMap], coefficient: f32) -> Option<Self> {
+        let (first, rest) = threats.split_first()?;
+
+        let nearest = rest
+            .iter()
+            .fold((*first).clone(), |acc, threat| acc.merge_nearest(threat));
+        Some(nearest.safety(coefficient))
+    }
 }
 impl Display for DijkstraMap {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let mut max = 0;
         for y in 0..self.map.area.size.y {
             for x in 0..self.map.area.size.x {
-                if let DijkstraMapValue::NonGoal(cost) = self.map[(x, y)] {
+                let p = self.map.to_world((x, y).into());
+                if let DijkstraMapValue::NonGoal(cost) = self.map[p] {
                     if cost > max {
                         max = cost;
                     }
@@ -232,8 +649,9 @@ impl Display for DijkstraMap {
         }
         for y in 0..self.map.area.size.y {
             for x in 0..self.map.area.size.x {
-                let tile_str = self.map[(x, y)].to_string();
-                let tile = match self.map[(x, y)] {
+                let p = self.map.to_world((x, y).into());
+                let tile_str = self.map[p].to_string();
+                let tile = match self.map[p] {
                     DijkstraMapValue::Goal => Paint::white(tile_str).to_string(),
                     DijkstraMapValue::Default => Paint::white(tile_str).to_string(),
                     DijkstraMapValue::NonGoal(value) => {
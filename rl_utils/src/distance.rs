@@ -0,0 +1,261 @@
+use std::f64::consts::SQRT_2;
+use std::fmt::Debug;
+
+use crate::{Coord, Map};
+
+/// The way distances between tiles are measured.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub enum Distance {
+    /// Diagonal steps count as one, the maximum of the horizontal and vertical distance.
+    Chebyshev,
+    /// Only orthogonal steps, the sum of the horizontal and vertical distance.
+    Manhattan,
+    /// The straight line distance.
+    Euclidean,
+    /// Diagonal steps count as the square root of two.
+    Octile,
+    /// Diagonal steps alternately count as one and two, as in many tabletop games.
+    RoguelikeDiagonal,
+}
+impl Distance {
+    /// The distance between two tiles.
+    pub fn measure(self, from: Coord, to: Coord) -> f64 {
+        let d = from.delta_abs(to);
+        let (long, short) = (d.x.max(d.y) as f64, d.x.min(d.y) as f64);
+        match self {
+            Distance::Chebyshev => long,
+            Distance::Manhattan => long + short,
+            Distance::Euclidean => long.hypot(short),
+            Distance::Octile => long + (SQRT_2 - 1.0) * short,
+            Distance::RoguelikeDiagonal => long + (short / 2.0).floor(),
+        }
+    }
+
+    /// Returns true when `to` lies at most `radius` away from `from`.
+    pub fn within(self, from: Coord, to: Coord, radius: isize) -> bool {
+        let d = from.delta_abs(to);
+        radius >= 0 && self.within_offset(d.x as usize, d.y as usize, radius as usize)
+    }
+
+    /// Returns true when a tile `dx` and `dy` tiles away lies at most `radius` away.
+    ///
+    /// Only uses integer math, for hot loops such as the radius checks of a field of view.
+    pub const fn within_offset(self, dx: usize, dy: usize, radius: usize) -> bool {
+        let (long, short) = if dx > dy { (dx, dy) } else { (dy, dx) };
+        match self {
+            Distance::Chebyshev => long <= radius,
+            Distance::Manhattan => long + short <= radius,
+            Distance::Euclidean => long * long + short * short <= radius * radius,
+            // long + (sqrt(2) - 1) * short <= radius, squared on both sides.
+            Distance::Octile => {
+                long <= radius
+                    && 2 * short * short <= (radius - long + short) * (radius - long + short)
+            }
+            Distance::RoguelikeDiagonal => long + short / 2 <= radius,
+        }
+    }
+
+    /// The weights of an orthogonal and a diagonal step, in which the path finders and
+    /// [crate::DijkstraMap::calculate_weighted] measure costs.
+    ///
+    /// The weight of a step is multiplied with the [crate::MovementCost] of the tile entered. Euclidean
+    /// distances are not possible on a grid and are treated as octile. Octile steps are
+    /// approximated as 5 and 7, roguelike diagonal steps average to 1.5.
+    pub fn step_weights(self) -> (usize, usize) {
+        match self {
+            Distance::Chebyshev => (1, 1),
+            Distance::Manhattan => (1, 2),
+            Distance::Euclidean | Distance::Octile => (5, 7),
+            Distance::RoguelikeDiagonal => (2, 3),
+        }
+    }
+
+    /// The cost of the cheapest path from `from` to `to` over tiles costing 1, in the units of
+    /// [Distance::step_weights].
+    ///
+    /// It never overestimates the cost of a path when every tile costs at least 1, which makes it
+    /// the heuristic of the path finders.
+    pub fn estimate(self, from: Coord, to: Coord) -> usize {
+        let (orthogonal, diagonal) = self.step_weights();
+        let d = from.delta_abs(to);
+        let (long, short) = (d.x.max(d.y) as usize, d.x.min(d.y) as usize);
+        orthogonal * (long - short) + diagonal.min(2 * orthogonal) * short
+    }
+}
+
+// The neighbours already visited by the forward and the backward raster scan, orthogonal ones
+// first.
+const FORWARD: [(isize, isize); 4] = [(0, -1), (-1, 0), (-1, -1), (1, -1)];
+const BACKWARD: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (-1, 1)];
+
+impl<T> Map<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    /// Calculates for every tile the distance towards the nearest source tile.
+    ///
+    /// Which tiles are sources, for example walls, is decided by `is_source`. Euclidean and
+    /// octile distances are exact and rounded to the nearest integer, see
+    /// [Map::squared_distance_transform] for the unrounded Euclidean values. Tiles are `u32::MAX`
+    /// when the map has no sources at all.
+    pub fn distance_transform<F>(&self, metric: Distance, is_source: F) -> Map<u32>
+    where
+        F: Fn(Coord, &T) -> bool,
+    {
+        if metric == Distance::Euclidean {
+            let mut dist = self.squared_distance_transform(is_source);
+            for d in dist.data.iter_mut().filter(|d| **d != u32::MAX) {
+                *d = (*d as f64).sqrt().round() as u32;
+            }
+            return dist;
+        }
+
+        let sources = self.sources(&is_source);
+        let mut dist = Map::new(sources.area.size);
+        dist.data = sources
+            .data
+            .iter()
+            .map(|d| if *d == 0 { 0.0 } else { f64::INFINITY })
+            .collect();
+        let diagonal = match metric {
+            Distance::Manhattan => f64::INFINITY,
+            Distance::Octile => SQRT_2,
+            Distance::RoguelikeDiagonal => 1.5,
+            _ => 1.0,
+        };
+        let size = dist.area.size;
+
+        // A forward and a backward raster scan suffice for metrics made of single steps.
+        for y in 0..size.y {
+            for x in 0..size.x {
+                chamfer(&mut dist, (x, y).into(), &FORWARD, diagonal);
+            }
+        }
+        for y in (0..size.y).rev() {
+            for x in (0..size.x).rev() {
+                chamfer(&mut dist, (x, y).into(), &BACKWARD, diagonal);
+            }
+        }
+
+        let mut result = Map::new(size).with_offset(self.area.position);
+        result.data = dist
+            .data
+            .iter()
+            .map(|d| match metric {
+                _ if d.is_infinite() => u32::MAX,
+                // Half diagonal steps are rounded down, which gives the alternating steps.
+                Distance::RoguelikeDiagonal => d.floor() as u32,
+                _ => d.round() as u32,
+            })
+            .collect();
+        result
+    }
+
+    /// Calculates for every tile the exact squared Euclidean distance towards the nearest source.
+    ///
+    /// This uses the linear time algorithm of Felzenszwalb and Huttenlocher.
+    pub fn squared_distance_transform<F>(&self, is_source: F) -> Map<u32>
+    where
+        F: Fn(Coord, &T) -> bool,
+    {
+        let mut dist = self.sources(&is_source);
+        let size = dist.area.size;
+        let mut line = vec![];
+
+        for x in 0..size.x {
+            line.clear();
+            line.extend((0..size.y).map(|y| dist[(x, y)]));
+            let column = squared_distance_1d(&line);
+            for (y, d) in column.into_iter().enumerate() {
+                dist[(x, y as isize)] = d;
+            }
+        }
+        for y in 0..size.y {
+            line.clear();
+            line.extend((0..size.x).map(|x| dist[(x, y)]));
+            let row = squared_distance_1d(&line);
+            for (x, d) in row.into_iter().enumerate() {
+                dist[(x as isize, y)] = d;
+            }
+        }
+        dist.with_offset(self.area.position)
+    }
+
+    /// A map without offset, with 0 for the source tiles and `u32::MAX` for all others.
+    fn sources<F>(&self, is_source: &F) -> Map<u32>
+    where
+        F: Fn(Coord, &T) -> bool,
+    {
+        let mut dist = Map::new(self.area.size);
+        dist.fill(u32::MAX);
+        for y in 0..self.area.size.y {
+            for x in 0..self.area.size.x {
+                let p = self.to_world((x, y).into());
+                if is_source(p, &self[p]) {
+                    dist[(x, y)] = 0;
+                }
+            }
+        }
+        dist
+    }
+}
+
+fn chamfer(dist: &mut Map<f64>, p: Coord, offsets: &[(isize, isize)], diagonal: f64) {
+    for (i, offset) in offsets.iter().enumerate() {
+        let n = p + (*offset).into();
+        if n.x >= 0 && n.y >= 0 && n.x < dist.area.size.x && n.y < dist.area.size.y {
+            let d = dist[n] + if i < 2 { 1.0 } else { diagonal };
+            if d < dist[p] {
+                dist[p] = d;
+            }
+        }
+    }
+}
+
+/// The lower envelope of the parabolas rooted at the finite values of `f`.
+fn squared_distance_1d(f: &[u32]) -> Vec<u32> {
+    let sites: Vec<(i64, i64)> = f
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| **v != u32::MAX)
+        .map(|(q, v)| (q as i64, *v as i64))
+        .collect();
+    if sites.is_empty() {
+        return vec![u32::MAX; f.len()];
+    }
+
+    let intersect = |(p, fp): (i64, i64), (q, fq): (i64, i64)| -> f64 {
+        ((fq + q * q) - (fp + p * p)) as f64 / (2 * (q - p)) as f64
+    };
+
+    let mut hull: Vec<(i64, i64)> = vec![];
+    let mut starts: Vec<f64> = vec![];
+    for site in sites {
+        while let Some(last) = hull.last() {
+            let s = intersect(*last, site);
+            if s <= starts[starts.len() - 1] {
+                hull.pop();
+                starts.pop();
+            } else {
+                break;
+            }
+        }
+        let start = match hull.last() {
+            Some(last) => intersect(*last, site),
+            None => f64::NEG_INFINITY,
+        };
+        hull.push(site);
+        starts.push(start);
+    }
+
+    let mut k = 0;
+    (0..f.len() as i64)
+        .map(|q| {
+            while k + 1 < hull.len() && starts[k + 1] < q as f64 {
+                k += 1;
+            }
+            let (p, fp) = hull[k];
+            ((q - p) * (q - p) + fp).min(u32::MAX as i64 - 1) as u32
+        })
+        .collect()
+}
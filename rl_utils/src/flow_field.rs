@@ -0,0 +1,104 @@
+use crate::{Coord, DijkstraMap, Map, MapMovement, MapObject, MovementCost};
+
+/// A tile whose movement cost is raised by the number of units crowding it.
+#[derive(Debug, Clone, PartialEq)]
+struct Congested(MovementCost);
+impl MapObject for Congested {
+    fn is_transparent(&self) -> bool {
+        true
+    }
+    fn is_walkable(&self) -> MovementCost {
+        self.0
+    }
+}
+
+/// Stores for every tile the direction of the next step towards the nearest goal.
+///
+/// When many units chase the same goals, looking up their direction is cheaper than searching
+/// the best neighbour in a [DijkstraMap] for each of them. Goals and tiles from which no goal can
+/// be reached have no direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowField {
+    pub map: Map<Option<Coord>>,
+    movement: MapMovement,
+}
+impl FlowField {
+    /// Creates a flow field following a calculated [DijkstraMap] downhill.
+    pub fn from_dijkstra_map(dmap: &DijkstraMap) -> Self {
+        let mut map = Map::new(dmap.map.area.size).with_offset(dmap.map.area.position);
+        map.fill(None);
+
+        for y in 0..map.area.size.y {
+            for x in 0..map.area.size.x {
+                let p = map.to_world((x, y).into());
+                map[p] = dmap.next_step(p).map(|n| n - p);
+            }
+        }
+        FlowField {
+            map,
+            movement: dmap.movement(),
+        }
+    }
+
+    /// Creates a flow field towards `goals`, using the [MovementCost] of the tiles in `terrain`.
+    pub fn from_map<T>(terrain: &Map<T>, goals: &[Coord], movement: MapMovement) -> Self
+    where
+        T: MapObject,
+    {
+        let dmap = DijkstraMap::new(terrain.area.size).with_offset(terrain.area.position);
+        let dmap = goals
+            .iter()
+            .fold(dmap, |dmap, g| dmap.with_goal(*g))
+            .with_movement(movement)
+            .with_movement_costs(terrain)
+            .calculate_weighted();
+        Self::from_dijkstra_map(&dmap)
+    }
+
+    /// Like [FlowField::from_map], but every unit of `congestion` on a tile adds to the cost of
+    /// entering it.
+    ///
+    /// Crowded tiles become more expensive, so the field starts sending units through parallel
+    /// corridors instead of letting them queue up behind each other.
+    pub fn from_map_with_congestion<T>(
+        terrain: &Map<T>,
+        goals: &[Coord],
+        movement: MapMovement,
+        congestion: &Map<usize>,
+    ) -> Self
+    where
+        T: MapObject,
+    {
+        assert!(terrain.area == congestion.area);
+
+        let mut congested = Map::new(terrain.area.size).with_offset(terrain.area.position);
+        congested.fill(Congested(MovementCost::Impossible));
+        for y in 0..terrain.area.size.y {
+            for x in 0..terrain.area.size.x {
+                let p = terrain.to_world((x, y).into());
+                congested[p] = Congested(match terrain[p].is_walkable() {
+                    MovementCost::Possible(cost) => MovementCost::Possible(cost + congestion[p]),
+                    MovementCost::Impossible => MovementCost::Impossible,
+                });
+            }
+        }
+        Self::from_map(&congested, goals, movement)
+    }
+
+    pub fn movement(&self) -> MapMovement {
+        self.movement
+    }
+
+    /// The offset of the next step from `pos`, e.g. (1,0) for a step to the right.
+    pub fn direction(&self, pos: Coord) -> Option<Coord> {
+        if !self.map.area.point_within(pos) {
+            return None;
+        }
+        self.map[pos]
+    }
+
+    /// The tile to move to from `pos`.
+    pub fn next_step(&self, pos: Coord) -> Option<Coord> {
+        self.direction(pos).map(|d| pos + d)
+    }
+}
@@ -0,0 +1,161 @@
+use pathfinding::undirected::kruskal::kruskal_indices;
+use triangulation::{Delaunay, Point};
+
+use crate::{Area, Coord, Distance, Map};
+
+/// An undirected edge between two sites, as the indices of both sites with the lowest first.
+pub type Edge = (usize, usize);
+
+fn squared(a: Coord, b: Coord) -> isize {
+    let d = a - b;
+    d.x * d.x + d.y * d.y
+}
+
+/// Whether `c` lies on the segment from `a` to `b`, but not on either end.
+fn between(a: Coord, b: Coord, c: Coord) -> bool {
+    let (ab, ac) = (b - a, c - a);
+    ab.x * ac.y == ab.y * ac.x && c != a && c != b && squared(a, c) + squared(c, b) < squared(a, b)
+}
+
+/// The edges of the Delaunay triangulation of `sites`, sorted.
+///
+/// Sites which lie on a single line are connected to their neighbours along it, and sites at the
+/// same position are connected to each other.
+pub fn delaunay(sites: &[Coord]) -> Vec<Edge> {
+    // The triangulation ignores duplicates, so every position is triangulated once.
+    let mut order: Vec<usize> = (0..sites.len()).collect();
+    order.sort_by_key(|i| (sites[*i], *i));
+    let mut unique: Vec<usize> = vec![];
+    let mut edges = vec![];
+    for i in order {
+        match unique.last() {
+            Some(last) if sites[*last] == sites[i] => edges.push((*last, i)),
+            _ => unique.push(i),
+        }
+    }
+
+    let points: Vec<Point> = unique.iter().map(|i| sites[*i].into()).collect();
+    match Delaunay::new(&points) {
+        Some(result) => {
+            let dcel = &result.dcel;
+            for e in 0..dcel.vertices.len() {
+                let (a, b) = (dcel.vertices[e], dcel.vertices[dcel.next_edge(e)]);
+                // Inner edges appear twice, once for each triangle.
+                if a < b || dcel.twin(e).is_none() {
+                    edges.push((unique[a], unique[b]));
+                }
+            }
+        }
+        // No triangle fits, so all sites lie on a line and are sorted along it.
+        None => edges.extend(unique.windows(2).map(|w| (w[0], w[1]))),
+    }
+
+    for edge in edges.iter_mut() {
+        if edge.0 > edge.1 {
+            *edge = (edge.1, edge.0);
+        }
+    }
+    // Flat triangles along collinear sites add edges which pass through another site.
+    edges.retain(|(a, b)| {
+        let (a, b) = (sites[*a], sites[*b]);
+        !unique.iter().any(|c| between(a, b, sites[*c]))
+    });
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// The euclidean minimum spanning tree of `sites`, sorted.
+///
+/// Connects all sites with the shortest total length of edges, for connecting rooms with as few
+/// corridors as possible.
+pub fn minimum_spanning_tree(sites: &[Coord]) -> Vec<Edge> {
+    let weighted: Vec<_> = delaunay(sites)
+        .into_iter()
+        .map(|(a, b)| (a, b, squared(sites[a], sites[b])))
+        .collect();
+    let mut edges: Vec<Edge> = kruskal_indices(sites.len(), &weighted)
+        .map(|(a, b, _)| (a, b))
+        .collect();
+    edges.sort_unstable();
+    edges
+}
+
+/// The relative neighbourhood graph of `sites`, sorted.
+///
+/// Two sites are connected unless another site is closer to both of them than they are to each
+/// other. Contains the minimum spanning tree, and adds a few loops.
+pub fn relative_neighbourhood(sites: &[Coord]) -> Vec<Edge> {
+    delaunay(sites)
+        .into_iter()
+        .filter(|(a, b)| {
+            let (a, b) = (sites[*a], sites[*b]);
+            let length = squared(a, b);
+            !sites
+                .iter()
+                .any(|c| squared(a, *c) < length && squared(b, *c) < length)
+        })
+        .collect()
+}
+
+/// The Gabriel graph of `sites`, sorted.
+///
+/// Two sites are connected unless another site lies within or on the circle which has their
+/// connection as diameter. Contains the relative neighbourhood graph.
+pub fn gabriel(sites: &[Coord]) -> Vec<Edge> {
+    delaunay(sites)
+        .into_iter()
+        .filter(|(a, b)| {
+            let (a, b) = (sites[*a], sites[*b]);
+            let length = squared(a, b);
+            !sites
+                .iter()
+                .any(|c| *c != a && *c != b && squared(a, *c) + squared(b, *c) <= length)
+        })
+        .collect()
+}
+
+/// Assigns every tile of `area` to the region of the nearest site measured with `metric`, by
+/// the index of the site.
+///
+/// Ties go to the site with the lowest index. Sites may lie outside of the area, and a site
+/// does not need to have any tiles.
+pub fn voronoi(sites: &[Coord], area: Area, metric: Distance) -> Map<usize> {
+    assert!(!sites.is_empty());
+    let mut map = Map::new(area.size).with_offset(area.position);
+    map.fill_each(|p| {
+        let mut nearest = (0, f64::MAX);
+        for (i, site) in sites.iter().enumerate() {
+            let d = metric.measure(*site, p);
+            if d < nearest.1 {
+                nearest = (i, d);
+            }
+        }
+        nearest.0
+    });
+    map
+}
+
+/// Lloyd relaxation, moves every site to the center of its [voronoi] region within `area`,
+/// `iterations` times.
+///
+/// The sites spread out more evenly with every iteration. Sites without a region stay where they
+/// are.
+pub fn lloyd(sites: &[Coord], area: Area, metric: Distance, iterations: usize) -> Vec<Coord> {
+    let mut sites = sites.to_vec();
+    for _ in 0..iterations {
+        let regions = voronoi(&sites, area, metric);
+        let mut sums = vec![(0, 0, 0); sites.len()];
+        for (p, region) in regions.iter() {
+            let sum = &mut sums[region];
+            *sum = (sum.0 + p.x, sum.1 + p.y, sum.2 + 1);
+        }
+        for (site, (x, y, count)) in sites.iter_mut().zip(sums) {
+            if count > 0 {
+                let center = |sum: isize| (sum as f64 / count as f64).round() as isize;
+                *site = Coord::new(center(x), center(y));
+            }
+        }
+    }
+    sites
+}
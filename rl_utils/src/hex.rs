@@ -0,0 +1,370 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{self, Debug};
+use std::iter;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Sub, SubAssign};
+
+use crate::path::astar_by;
+use crate::{Coord, DijkstraMapValue, Map, MapObject, MovementCost, Path};
+
+/// How a rectangle of hexes is stored in rows and columns, see
+/// [Red Blob Games](https://www.redblobgames.com/grids/hexagons/#coordinates-offset).
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub enum OffsetLayout {
+    /// Pointy topped hexes, odd rows are shoved right.
+    OddR,
+    /// Pointy topped hexes, even rows are shoved right.
+    EvenR,
+    /// Flat topped hexes, odd columns are shoved down.
+    OddQ,
+    /// Flat topped hexes, even columns are shoved down.
+    EvenQ,
+}
+
+/// The position of a hex in axial coordinates.
+///
+/// The third cube coordinate `s` follows from `q + r + s == 0`.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub struct HexCoord {
+    pub q: isize,
+    pub r: isize,
+}
+impl HexCoord {
+    /// The six neighbouring directions, counter clockwise starting with the east for pointy
+    /// topped hexes.
+    pub const DIRECTIONS: [HexCoord; 6] = [
+        HexCoord { q: 1, r: 0 },
+        HexCoord { q: 1, r: -1 },
+        HexCoord { q: 0, r: -1 },
+        HexCoord { q: -1, r: 0 },
+        HexCoord { q: -1, r: 1 },
+        HexCoord { q: 0, r: 1 },
+    ];
+
+    pub fn new(q: isize, r: isize) -> Self {
+        HexCoord { q, r }
+    }
+
+    pub fn from_cube(q: isize, r: isize, s: isize) -> Self {
+        assert!(q + r + s == 0);
+        HexCoord { q, r }
+    }
+
+    pub fn s(self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn cube(self) -> (isize, isize, isize) {
+        (self.q, self.r, self.s())
+    }
+
+    /// Converts the column and row of a hex in a rectangular layout.
+    pub fn from_offset(pos: Coord, layout: OffsetLayout) -> Self {
+        let Coord { x: col, y: row } = pos;
+        match layout {
+            OffsetLayout::OddR => HexCoord::new(col - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => HexCoord::new(col - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => HexCoord::new(col, row - (col - (col & 1)) / 2),
+            OffsetLayout::EvenQ => HexCoord::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+
+    /// The column and row of the hex in a rectangular layout.
+    pub fn to_offset(self, layout: OffsetLayout) -> Coord {
+        let HexCoord { q, r } = self;
+        match layout {
+            OffsetLayout::OddR => Coord::new(q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => Coord::new(q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => Coord::new(q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => Coord::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(self, other: HexCoord) -> isize {
+        let d = self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    pub fn neighbours(self) -> [HexCoord; 6] {
+        HexCoord::DIRECTIONS.map(|d| self + d)
+    }
+
+    /// Lazy line of hexes from `self` up to and including `end`.
+    pub fn line(self, end: HexCoord) -> HexLine {
+        HexLine {
+            start: self,
+            end,
+            steps: self.distance(end),
+            index: 0,
+        }
+    }
+
+    /// Iterates over the hexes at `radius` steps, counter clockwise.
+    pub fn ring(self, radius: isize) -> impl Iterator<Item = HexCoord> {
+        let mut corner = self + HexCoord::DIRECTIONS[4] * radius;
+        let mut corners = [corner; 6];
+        for (side, c) in corners.iter_mut().enumerate() {
+            *c = corner;
+            corner += HexCoord::DIRECTIONS[side] * radius;
+        }
+        let center = iter::once(self).filter(move |_| radius == 0);
+        center.chain((0..6).flat_map(move |side| {
+            (0..radius).map(move |step| corners[side] + HexCoord::DIRECTIONS[side] * step)
+        }))
+    }
+
+    /// Iterates over the hexes around `self` ring by ring, starting with `self` itself.
+    ///
+    /// The first `1 + 3r(r + 1)` hexes lie within `r` steps. The spiral never ends, limit it with
+    /// [Iterator::take] or [Iterator::take_while].
+    pub fn spiral(self) -> impl Iterator<Item = HexCoord> {
+        (0..).flat_map(move |radius| self.ring(radius))
+    }
+}
+impl Add for HexCoord {
+    type Output = HexCoord;
+
+    fn add(self, o: HexCoord) -> HexCoord {
+        HexCoord::new(self.q + o.q, self.r + o.r)
+    }
+}
+impl AddAssign for HexCoord {
+    fn add_assign(&mut self, o: HexCoord) {
+        *self = *self + o;
+    }
+}
+impl Sub for HexCoord {
+    type Output = HexCoord;
+
+    fn sub(self, o: HexCoord) -> HexCoord {
+        HexCoord::new(self.q - o.q, self.r - o.r)
+    }
+}
+impl SubAssign for HexCoord {
+    fn sub_assign(&mut self, o: HexCoord) {
+        *self = *self - o;
+    }
+}
+impl Mul<isize> for HexCoord {
+    type Output = HexCoord;
+
+    fn mul(self, factor: isize) -> HexCoord {
+        HexCoord::new(self.q * factor, self.r * factor)
+    }
+}
+impl From<(isize, isize)> for HexCoord {
+    fn from(pos: (isize, isize)) -> Self {
+        HexCoord::new(pos.0, pos.1)
+    }
+}
+impl fmt::Display for HexCoord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({},{},{})", self.q, self.r, self.s())
+    }
+}
+
+/// Lazy line of hexes, see [HexCoord::line].
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct HexLine {
+    start: HexCoord,
+    end: HexCoord,
+    steps: isize,
+    index: isize,
+}
+impl Iterator for HexLine {
+    type Item = HexCoord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index > self.steps {
+            return None;
+        }
+        let i = self.index;
+        self.index += 1;
+        if self.steps == 0 {
+            return Some(self.start);
+        }
+
+        // Nudges the line off the edges between two hexes, so ties are always broken the same way.
+        let t = i as f64 / self.steps as f64;
+        let lerp = |a: isize, b: isize, nudge: f64| a as f64 + nudge + (b - a) as f64 * t;
+        let q = lerp(self.start.q, self.end.q, 1e-6);
+        let r = lerp(self.start.r, self.end.r, 2e-6);
+        Some(round_cube(q, r, -q - r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.steps + 1 - self.index).max(0) as usize;
+        (left, Some(left))
+    }
+}
+
+/// Rounds fractional cube coordinates to the hex containing them.
+fn round_cube(q: f64, r: f64, s: f64) -> HexCoord {
+    let (rq, rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        HexCoord::new((-rr - rs) as isize, rr as isize)
+    } else if dr > ds {
+        HexCoord::new(rq as isize, (-rq - rs) as isize)
+    } else {
+        HexCoord::new(rq as isize, rr as isize)
+    }
+}
+
+/// A rectangle of hexes, stored in a [Map] by their offset coordinates.
+///
+/// Hexes are addressed with [HexCoord], the rectangle is `map.area` in the columns and rows of
+/// `layout`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HexMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    pub map: Map<T>,
+    pub layout: OffsetLayout,
+}
+impl<T> HexMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    pub fn new(size: Coord, layout: OffsetLayout) -> Self {
+        HexMap {
+            map: Map::new(size),
+            layout,
+        }
+    }
+
+    /// Moves the rectangle, `offset` is the column and row of its top left hex.
+    pub fn with_offset(mut self, offset: Coord) -> Self {
+        self.map.area.position = offset;
+        self
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.map.fill(value);
+    }
+
+    pub fn contains(&self, pos: HexCoord) -> bool {
+        self.map.area.point_within(pos.to_offset(self.layout))
+    }
+
+    pub fn get(&self, pos: HexCoord) -> Option<&T> {
+        self.map.get(pos.to_offset(self.layout))
+    }
+
+    pub fn get_mut(&mut self, pos: HexCoord) -> Option<&mut T> {
+        self.map.get_mut(pos.to_offset(self.layout))
+    }
+
+    /// Iterates over all hexes, row by row in the offset layout.
+    pub fn iter(&self) -> impl Iterator<Item = (HexCoord, &T)> + '_ {
+        let area = self.map.area;
+        area.iter()
+            .map(move |p| (HexCoord::from_offset(p, self.layout), &self.map[p]))
+    }
+}
+impl<T> Index<HexCoord> for HexMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    type Output = T;
+
+    fn index(&self, pos: HexCoord) -> &T {
+        &self.map[pos.to_offset(self.layout)]
+    }
+}
+impl<T> IndexMut<HexCoord> for HexMap<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    fn index_mut(&mut self, pos: HexCoord) -> &mut T {
+        let offset = pos.to_offset(self.layout);
+        &mut self.map[offset]
+    }
+}
+
+impl<T> HexMap<T>
+where
+    T: MapObject,
+{
+    /// Like [Map::walkable_tiles], the neighbours of `pos` which can be walked on together with
+    /// the cost of entering them.
+    pub fn walkable_tiles(&self, pos: HexCoord) -> Vec<(HexCoord, usize)> {
+        let mut retvec = vec![];
+        for n in pos.neighbours().iter() {
+            if let Some(MovementCost::Possible(cost)) = self.get(*n).map(|t| t.is_walkable()) {
+                retvec.push((*n, cost));
+            }
+        }
+        retvec
+    }
+
+    /// Finds the cheapest path from `start` to `goal`, like [Map::astar].
+    pub fn astar(&self, start: HexCoord, goal: HexCoord) -> Option<Path<HexCoord>> {
+        let heuristic = |pos: HexCoord| pos.distance(goal) as usize;
+        astar_by(start, goal, |pos| self.walkable_tiles(pos), heuristic, None)
+    }
+
+    /// Calculates the cheapest cost towards the nearest goal for every hex, like
+    /// [crate::DijkstraMap::calculate_weighted].
+    ///
+    /// Hexes which are not walkable are [DijkstraMapValue::Impassable], hexes from which no goal
+    /// can be reached stay [DijkstraMapValue::Default].
+    pub fn dijkstra_map(&self, goals: &[HexCoord]) -> HexMap<DijkstraMapValue> {
+        let mut field = HexMap {
+            map: Map::new(self.map.area.size).with_offset(self.map.area.position),
+            layout: self.layout,
+        };
+        field.fill(DijkstraMapValue::Default);
+        for (pos, tile) in self.iter() {
+            if tile.is_walkable() == MovementCost::Impossible {
+                field[pos] = DijkstraMapValue::Impassable;
+            }
+        }
+
+        let mut queue = BinaryHeap::new();
+        for goal in goals.iter().filter(|g| self.contains(**g)) {
+            field[*goal] = DijkstraMapValue::Goal;
+            queue.push(Reverse((0, *goal)));
+        }
+        while let Some(Reverse((cost, current))) = queue.pop() {
+            if cost > field[current].to_value() {
+                continue;
+            }
+            for (neighbour, step) in self.walkable_tiles(current) {
+                let next = cost + step as isize;
+                if let DijkstraMapValue::Default | DijkstraMapValue::NonGoal(_) = field[neighbour] {
+                    if next < field[neighbour].to_value() {
+                        field[neighbour] = DijkstraMapValue::NonGoal(next);
+                        queue.push(Reverse((next, neighbour)));
+                    }
+                }
+            }
+        }
+        field
+    }
+}
+
+impl HexMap<DijkstraMapValue> {
+    /// Returns the neighbour of `from` with the lowest value, if that is lower than `from` itself,
+    /// like [crate::DijkstraMap::next_step].
+    pub fn next_step(&self, from: HexCoord) -> Option<HexCoord> {
+        let mut best = match self.get(from) {
+            Some(DijkstraMapValue::Goal) | None => return None,
+            Some(v) => v.to_value(),
+        };
+        let mut step = None;
+        for neighbour in from.neighbours().iter() {
+            if let Some(v @ (DijkstraMapValue::Goal | DijkstraMapValue::NonGoal(_))) =
+                self.get(*neighbour)
+            {
+                if v.to_value() < best {
+                    best = v.to_value();
+                    step = Some(*neighbour);
+                }
+            }
+        }
+        step
+    }
+}
@@ -0,0 +1,275 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use crate::{Area, Coord, Map, MapObject, MovementCost};
+
+trait Layer: Any + Debug {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    fn set_offset(&mut self, offset: Coord);
+    fn resize(&mut self, area: Area);
+}
+
+#[derive(Debug)]
+struct LayerData<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    map: Map<T>,
+    fill: T,
+}
+impl<T> Layer for LayerData<T>
+where
+    T: Debug + Clone + PartialEq + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn set_offset(&mut self, offset: Coord) {
+        self.map.area.position = offset;
+    }
+
+    fn resize(&mut self, area: Area) {
+        let mut map = Map::new(area.size).with_offset(area.position);
+        map.fill(self.fill.clone());
+        for y in 0..self.map.area.size.y {
+            for x in 0..self.map.area.size.x {
+                let p = self.map.to_world((x, y).into());
+                if area.point_within(p) {
+                    map[p] = self.map[p].clone();
+                }
+            }
+        }
+        self.map = map;
+    }
+}
+
+/// The transparency and movement cost of a tile, derived from one or more layers.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct TileProperties {
+    pub transparent: bool,
+    pub walkable: MovementCost,
+}
+impl TileProperties {
+    pub fn of<T: MapObject>(tile: &T) -> Self {
+        TileProperties {
+            transparent: tile.is_transparent(),
+            walkable: tile.is_walkable(),
+        }
+    }
+
+    /// Stacks `other` on top of this tile.
+    ///
+    /// The result is only transparent when both are, and costs as much as the more expensive of
+    /// the two, so a closed door on a floor tile blocks both vision and movement.
+    pub fn and(self, other: TileProperties) -> Self {
+        let walkable = match (self.walkable, other.walkable) {
+            (MovementCost::Possible(a), MovementCost::Possible(b)) => {
+                MovementCost::Possible(a.max(b))
+            }
+            _ => MovementCost::Impossible,
+        };
+        TileProperties {
+            transparent: self.transparent && other.transparent,
+            walkable,
+        }
+    }
+}
+impl MapObject for TileProperties {
+    fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+    fn is_walkable(&self) -> MovementCost {
+        self.walkable
+    }
+}
+
+/// Several maps of different tile types, for example terrain, items and lighting, which all
+/// share the same [Area].
+///
+/// Layers are identified by their tile type, or by their tile type and a name when there are
+/// several layers of the same type. Looking up a layer returns the [Map] itself, so reading and
+/// writing tiles costs the same as with a standalone map.
+#[derive(Debug)]
+pub struct LayeredMap {
+    area: Area,
+    layers: HashMap<(TypeId, &'static str), Box<dyn Layer>>,
+}
+impl LayeredMap {
+    pub fn new(size: Coord) -> Self {
+        LayeredMap {
+            area: Area::new((0, 0).into(), size),
+            layers: HashMap::new(),
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Coord) -> Self {
+        self.set_offset(offset);
+        self
+    }
+
+    /// Adds a layer with every tile set to `fill`, see [LayeredMap::add_layer].
+    pub fn with_layer<T>(mut self, fill: T) -> Self
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.add_layer(fill);
+        self
+    }
+
+    /// Adds a named layer with every tile set to `fill`, see [LayeredMap::add_named_layer].
+    pub fn with_named_layer<T>(mut self, name: &'static str, fill: T) -> Self
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.add_named_layer(name, fill);
+        self
+    }
+
+    pub fn area(&self) -> Area {
+        self.area
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Adds the layer for tiles of type `T`, replacing an existing one.
+    ///
+    /// `fill` is used for all tiles, and for the new tiles when the map is resized.
+    pub fn add_layer<T>(&mut self, fill: T) -> &mut Map<T>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.add_named_layer("", fill)
+    }
+
+    /// Like [LayeredMap::add_layer], for when several layers share the same tile type.
+    pub fn add_named_layer<T>(&mut self, name: &'static str, fill: T) -> &mut Map<T>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        let mut map = Map::new(self.area.size).with_offset(self.area.position);
+        map.fill(fill.clone());
+        let key = (TypeId::of::<T>(), name);
+        self.layers.insert(key, Box::new(LayerData { map, fill }));
+        self.named_layer_mut(name).unwrap()
+    }
+
+    pub fn layer<T>(&self) -> Option<&Map<T>>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.named_layer("")
+    }
+
+    /// Mutable version of [LayeredMap::layer], the area of the layer must not be changed.
+    pub fn layer_mut<T>(&mut self) -> Option<&mut Map<T>>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.named_layer_mut("")
+    }
+
+    pub fn named_layer<T>(&self, name: &'static str) -> Option<&Map<T>>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.layers
+            .get(&(TypeId::of::<T>(), name))
+            .and_then(|l| l.as_any().downcast_ref::<LayerData<T>>())
+            .map(|l| &l.map)
+    }
+
+    /// Mutable version of [LayeredMap::named_layer], the area of the layer must not be changed.
+    pub fn named_layer_mut<T>(&mut self, name: &'static str) -> Option<&mut Map<T>>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.layers
+            .get_mut(&(TypeId::of::<T>(), name))
+            .and_then(|l| l.as_any_mut().downcast_mut::<LayerData<T>>())
+            .map(|l| &mut l.map)
+    }
+
+    /// Removes the layer for tiles of type `T` and returns it.
+    pub fn remove_layer<T>(&mut self) -> Option<Map<T>>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.remove_named_layer("")
+    }
+
+    pub fn remove_named_layer<T>(&mut self, name: &'static str) -> Option<Map<T>>
+    where
+        T: Debug + Clone + PartialEq + 'static,
+    {
+        self.layers
+            .remove(&(TypeId::of::<T>(), name))
+            .and_then(|l| l.into_any().downcast::<LayerData<T>>().ok())
+            .map(|l| l.map)
+    }
+
+    /// Moves all layers to `offset`, the tiles move along.
+    pub fn set_offset(&mut self, offset: Coord) {
+        self.area.position = offset;
+        for layer in self.layers.values_mut() {
+            layer.set_offset(offset);
+        }
+    }
+
+    /// Changes the area of all layers.
+    ///
+    /// Tiles keep their world position, tiles outside of `area` are dropped and new tiles are set
+    /// to the fill value of their layer.
+    pub fn resize(&mut self, area: Area) {
+        self.area = area;
+        for layer in self.layers.values_mut() {
+            layer.resize(area);
+        }
+    }
+
+    /// Derives the transparency and movement cost of every tile, for example by stacking a door
+    /// layer on top of the terrain with [TileProperties::and].
+    ///
+    /// The result can be used with the path finding and analysis functions of [Map].
+    pub fn combine<F>(&self, func: F) -> Map<TileProperties>
+    where
+        F: Fn(Coord) -> TileProperties,
+    {
+        let mut map = Map::new(self.area.size).with_offset(self.area.position);
+        map.fill(TileProperties {
+            transparent: false,
+            walkable: MovementCost::Impossible,
+        });
+        for y in 0..self.area.size.y {
+            for x in 0..self.area.size.x {
+                let p = map.to_world((x, y).into());
+                map[p] = func(p);
+            }
+        }
+        map
+    }
+
+    /// Stacks the layers of tile types `A` and `B`, see [TileProperties::and].
+    pub fn combine_layers<A, B>(&self) -> Option<Map<TileProperties>>
+    where
+        A: MapObject + 'static,
+        B: MapObject + 'static,
+    {
+        let a = self.layer::<A>()?;
+        let b = self.layer::<B>()?;
+        Some(self.combine(|p| TileProperties::of(&a[p]).and(TileProperties::of(&b[p]))))
+    }
+}
@@ -3,17 +3,51 @@ extern crate triangulation;
 extern crate yansi;
 
 pub mod area;
+pub mod automaton;
+pub mod bitmap;
 pub mod ca;
+pub mod chunk_map;
 pub mod coord;
 pub mod dijkstra_map;
+pub mod distance;
+pub mod flow_field;
+pub mod geometry;
+pub mod hex;
+pub mod layered_map;
+pub mod line;
 pub mod map;
+pub mod noise;
+pub mod path;
+pub mod poisson;
+pub mod regions;
+pub mod shapes;
+pub mod transform;
 pub mod tranthong;
 
 pub use self::area::Area;
+pub use self::automaton::{Automaton, Boundary, LifeRule, Neighbourhood, ParseRuleError};
+pub use self::bitmap::{BitMap, BitMapIter};
 pub use self::ca::{ca_generate, CATile, CAparams, CA};
+pub use self::chunk_map::ChunkMap;
 pub use self::coord::Coord;
 pub use self::dijkstra_map::{DijkstraMap, DijkstraMapValue};
+pub use self::distance::Distance;
+pub use self::flow_field::FlowField;
+pub use self::geometry::{
+    delaunay, gabriel, lloyd, minimum_spanning_tree, relative_neighbourhood, voronoi, Edge,
+};
+pub use self::hex::{HexCoord, HexLine, HexMap, OffsetLayout};
+pub use self::layered_map::{LayeredMap, TileProperties};
+pub use self::line::{BresenhamLine, SupercoverLine, SymmetricLine};
 pub use self::map::{Map, MapIterator, MapMovement, MapObject, MovementCost};
+pub use self::noise::{
+    Fractal, FractalKind, Noise, OpenSimplex, Perlin, Value, Warp, Worley, WorleyValue,
+};
+pub use self::path::{HierarchicalMap, Path, PathConfig};
+pub use self::poisson::PoissonDisk;
+pub use self::regions::Regions;
+pub use self::shapes::{ring, spiral, Ellipse};
+pub use self::transform::Rotation;
 pub use self::tranthong::{tranthong, tranthong_func};
 
 #[cfg(test)]
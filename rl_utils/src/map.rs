@@ -40,6 +40,11 @@ impl MapMovement {
     }
 }
 
+/// A rectangle of tiles placed somewhere in the world.
+///
+/// Indexing with a [Coord] or a tuple, [Map::get] and [Map::get_mut] take world positions, which
+/// are shifted by the offset in `area.position`. [Map::get_local] and [Map::get_local_mut] take
+/// positions relative to the top left tile instead, see [Map::to_local] and [Map::to_world].
 #[derive(Debug, Clone, PartialEq)]
 pub struct Map<T>
 where
@@ -81,23 +86,161 @@ where
         });
     }
 
+    /// Converts a world position into a position relative to the top left tile of the map.
+    pub fn to_local(&self, pos: Coord) -> Coord {
+        pos - self.area.position
+    }
+
+    /// Converts a position relative to the top left tile of the map into a world position.
+    pub fn to_world(&self, pos: Coord) -> Coord {
+        pos + self.area.position
+    }
+
+    /// Returns the tile at the world position `pos`, or None when it lies outside of the map.
     pub fn get(&self, pos: Coord) -> Option<&T> {
-        self.data
-            .get((pos.y as usize * self.area.size.x as usize) + pos.x as usize)
+        self.get_local(self.to_local(pos))
     }
 
+    /// Mutable version of [Map::get].
     pub fn get_mut(&mut self, pos: Coord) -> Option<&mut T> {
-        self.data
-            .get_mut((pos.y as usize * self.area.size.x as usize) + pos.x as usize)
+        self.get_local_mut(self.to_local(pos))
+    }
+
+    /// Returns the tile at `pos` relative to the top left tile of the map, ignoring the offset.
+    pub fn get_local(&self, pos: Coord) -> Option<&T> {
+        self.local_index(pos).and_then(move |i| self.data.get(i))
+    }
+
+    /// Mutable version of [Map::get_local].
+    pub fn get_local_mut(&mut self, pos: Coord) -> Option<&mut T> {
+        self.local_index(pos)
+            .and_then(move |i| self.data.get_mut(i))
+    }
+
+    fn local_index(&self, pos: Coord) -> Option<usize> {
+        if pos.x >= 0 && pos.y >= 0 && pos.x < self.area.size.x && pos.y < self.area.size.y {
+            Some((pos.y * self.area.size.x + pos.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The index into `data` of the world position `pos`, panics when it lies outside of the map.
+    fn world_index(&self, pos: Coord) -> usize {
+        self.local_index(self.to_local(pos))
+            .unwrap_or_else(|| panic!("{:?} is outside of the map {:?}", pos, self.area))
     }
 
     pub fn iter(&'_ self) -> MapIterator<'_, T> {
-        MapIterator {
-            pos: 0,
-            size: self.area.size,
-            start: self.area.position,
-            map: self.data.as_slice(),
+        MapIterator::new(self.data.as_slice(), self.area.size).with_offset(self.area.position)
+    }
+
+    /// Like [Map::iter], but only over the tiles within `area`, or None when `area` does not lie
+    /// within this map.
+    pub fn area_iter(&'_ self, area: Area) -> Option<MapIterator<'_, T>> {
+        if !self.area.area_within(area) {
+            return None;
         }
+        let first = self.local_index(self.to_local(area.position)).unwrap_or(0);
+        Some(
+            MapIterator::new(&self.data[first..], area.size)
+                .with_offset(area.position)
+                .with_stride(self.area.size.x),
+        )
+    }
+
+    /// Iterates over the world position and a reference of every tile, row by row.
+    ///
+    /// Only the tiles which are filled in are visited, a map which is not filled yet has none.
+    pub fn iter_ref(&self) -> impl Iterator<Item = (Coord, &T)> + '_ {
+        let (width, start) = (self.area.size.x, self.area.position);
+        self.data.iter().enumerate().map(move |(i, t)| {
+            (
+                start + Coord::new(i as isize % width, i as isize / width),
+                t,
+            )
+        })
+    }
+
+    /// Mutable version of [Map::iter_ref].
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coord, &mut T)> + '_ {
+        let (width, start) = (self.area.size.x, self.area.position);
+        self.data.iter_mut().enumerate().map(move |(i, t)| {
+            (
+                start + Coord::new(i as isize % width, i as isize / width),
+                t,
+            )
+        })
+    }
+
+    /// Iterates over the rows of the map, from top to bottom.
+    ///
+    /// Like [Map::iter_ref], only the tiles which are filled in are visited, so a map which is
+    /// not filled yet has no rows.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        self.data.chunks(self.area.size.x.max(1) as usize)
+    }
+
+    /// Mutable version of [Map::rows].
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> + '_ {
+        let width = self.area.size.x.max(1) as usize;
+        self.data.chunks_mut(width)
+    }
+
+    /// The part of `area` which lies within the map, as a range of local columns and rows.
+    fn window_bounds(&self, area: Area) -> (Coord, Coord) {
+        let from = self.to_local(area.position);
+        let to = from + area.size;
+        let first = Coord::new(from.x.max(0), from.y.max(0));
+        let last = Coord::new(
+            to.x.min(self.area.size.x).max(first.x),
+            to.y.min(self.area.size.y).max(first.y),
+        );
+        (first, last)
+    }
+
+    /// Iterates over the tiles within `area`, skipping the parts of `area` outside of the map.
+    pub fn window(&self, area: Area) -> impl Iterator<Item = (Coord, &T)> + '_ {
+        let (first, last) = self.window_bounds(area);
+        let start = self.area.position;
+        self.rows()
+            .enumerate()
+            .skip(first.y as usize)
+            .take((last.y - first.y) as usize)
+            .flat_map(move |(y, row)| {
+                row[first.x as usize..last.x as usize]
+                    .iter()
+                    .enumerate()
+                    .map(move |(x, t)| (start + Coord::new(first.x + x as isize, y as isize), t))
+            })
+    }
+
+    /// Mutable version of [Map::window].
+    pub fn window_mut(&mut self, area: Area) -> impl Iterator<Item = (Coord, &mut T)> + '_ {
+        let (first, last) = self.window_bounds(area);
+        let start = self.area.position;
+        self.rows_mut()
+            .enumerate()
+            .skip(first.y as usize)
+            .take((last.y - first.y) as usize)
+            .flat_map(move |(y, row)| {
+                row[first.x as usize..last.x as usize]
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(x, t)| (start + Coord::new(first.x + x as isize, y as isize), t))
+            })
+    }
+
+    /// Iterates over the tiles next to `pos` which can be reached using `movement`.
+    pub fn neighbours(
+        &self,
+        pos: Coord,
+        movement: MapMovement,
+    ) -> impl Iterator<Item = (Coord, &T)> + '_ {
+        movement
+            .get_reachable_tiles()
+            .into_iter()
+            .filter_map(move |offset| self.get(pos + offset).map(|t| (pos + offset, t)))
     }
 }
 
@@ -137,9 +280,10 @@ where
     T: Display + Debug + Clone + PartialEq,
 {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        for y in 0..self.area.size.y {
-            for x in 0..self.area.size.x {
-                write!(f, "{}", self[(x, y)].to_string())?;
+        let rows = self.data.chunks(self.area.size.x as usize);
+        for row in rows.take(self.area.size.y as usize) {
+            for tile in row {
+                write!(f, "{}", tile.to_string())?;
             }
             writeln!(f)?;
         }
@@ -173,8 +317,7 @@ where
     type Output = T;
 
     fn index(&self, pos: Coord) -> &Self::Output {
-        assert!(self.area.point_within(pos));
-        &self.data[(pos.y as usize * self.area.size.x as usize) + pos.x as usize]
+        &self.data[self.world_index(pos)]
     }
 }
 impl<T> Index<&Coord> for Map<T>
@@ -184,8 +327,7 @@ where
     type Output = T;
 
     fn index(&self, pos: &Coord) -> &Self::Output {
-        assert!(self.area.point_within(*pos));
-        &self.data[(pos.y as usize * self.area.size.x as usize) + pos.x as usize]
+        &self.data[self.world_index(*pos)]
     }
 }
 impl<T> IndexMut<Coord> for Map<T>
@@ -193,8 +335,8 @@ where
     T: Debug + Clone + PartialEq,
 {
     fn index_mut(&'_ mut self, pos: Coord) -> &'_ mut Self::Output {
-        assert!(self.area.point_within(pos));
-        &mut self.data[(pos.y as usize * self.area.size.x as usize) + pos.x as usize]
+        let i = self.world_index(pos);
+        &mut self.data[i]
     }
 }
 impl<T> IndexMut<&Coord> for Map<T>
@@ -202,8 +344,8 @@ where
     T: Debug + Clone + PartialEq,
 {
     fn index_mut(&'_ mut self, pos: &Coord) -> &'_ mut Self::Output {
-        assert!(self.area.point_within(*pos));
-        &mut self.data[(pos.y as usize * self.area.size.x as usize) + pos.x as usize]
+        let i = self.world_index(*pos);
+        &mut self.data[i]
     }
 }
 impl<T> Index<(usize, usize)> for Map<T>
@@ -213,8 +355,7 @@ where
     type Output = T;
 
     fn index(&self, pos: (usize, usize)) -> &Self::Output {
-        assert!(self.area.point_within(pos.into()));
-        &self.data[(pos.1 * self.area.size.x as usize) + pos.0]
+        &self.data[self.world_index(pos.into())]
     }
 }
 impl<T> IndexMut<(usize, usize)> for Map<T>
@@ -222,8 +363,8 @@ where
     T: Debug + Clone + PartialEq,
 {
     fn index_mut(&'_ mut self, pos: (usize, usize)) -> &'_ mut Self::Output {
-        assert!(self.area.point_within(pos.into()));
-        &mut self.data[(pos.1 * self.area.size.x as usize) + pos.0]
+        let i = self.world_index(pos.into());
+        &mut self.data[i]
     }
 }
 
@@ -234,8 +375,7 @@ where
     type Output = T;
 
     fn index(&self, pos: (isize, isize)) -> &Self::Output {
-        assert!(self.area.point_within(pos.into()));
-        &self.data[(pos.1 as usize * self.area.size.x as usize) + pos.0 as usize]
+        &self.data[self.world_index(pos.into())]
     }
 }
 impl<T> IndexMut<(isize, isize)> for Map<T>
@@ -243,23 +383,53 @@ where
     T: Debug + Clone + PartialEq,
 {
     fn index_mut(&'_ mut self, pos: (isize, isize)) -> &'_ mut Self::Output {
-        assert!(self.area.point_within(pos.into()));
-        &mut self.data[(pos.1 as usize * self.area.size.x as usize) + pos.0 as usize]
+        let i = self.world_index(pos.into());
+        &mut self.data[i]
     }
 }
 
 /// Iterator which returns a Tuple containing a (Coord, T)
 ///
-/// This is used to retrieve map contents, for example from a [Dungeon].
+/// This is used to retrieve map contents, for example from a [Dungeon]. It walks `size` tiles
+/// per row, starting at the first element of `map`, and skips `stride` elements to get to the
+/// next row, so it can iterate over a part of a larger map.
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub struct MapIterator<'a, T>
 where
     T: Clone,
 {
-    pub pos: isize,
-    pub size: Coord,
-    pub start: Coord,
-    pub map: &'a [T],
+    pos: isize,
+    size: Coord,
+    start: Coord,
+    stride: isize,
+    map: &'a [T],
+}
+impl<'a, T> MapIterator<'a, T>
+where
+    T: Clone,
+{
+    /// Iterates over the `size` tiles stored row by row in `map`, starting at (0,0).
+    pub fn new(map: &'a [T], size: Coord) -> Self {
+        MapIterator {
+            pos: 0,
+            size,
+            start: (0, 0).into(),
+            stride: size.x,
+            map,
+        }
+    }
+
+    /// Sets the world position of the first tile.
+    pub fn with_offset(mut self, offset: Coord) -> Self {
+        self.start = offset;
+        self
+    }
+
+    /// Sets the number of elements between the starts of two rows, by default the width.
+    pub fn with_stride(mut self, stride: isize) -> Self {
+        self.stride = stride;
+        self
+    }
 }
 impl<'a, T> Iterator for MapIterator<'a, T>
 where
@@ -270,13 +440,10 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         if self.pos < self.size.x * self.size.y {
             self.pos += 1;
+            let (x, y) = ((self.pos - 1) % self.size.x, (self.pos - 1) / self.size.x);
             Some((
-                (
-                    (((self.pos - 1) % self.size.x) + self.start.x),
-                    (((self.pos - 1) / self.size.x) + self.start.y),
-                )
-                    .into(),
-                self.map[(self.pos - 1) as usize].clone(),
+                (x + self.start.x, y + self.start.y).into(),
+                self.map[(y * self.stride + x) as usize].clone(),
             ))
         } else {
             None
@@ -0,0 +1,344 @@
+use crate::{Area, Map};
+
+/// A seeded coherent noise function over the plane.
+///
+/// Noise is a pure function of the seed and the position, so maps filled for neighbouring areas
+/// line up without seams.
+pub trait Noise {
+    /// The noise at `(x, y)`, roughly within -1 and 1 unless stated otherwise.
+    fn get(&self, x: f64, y: f64) -> f64;
+
+    /// Samples the noise for every tile of `area`, the tile at world position `pos` gets the
+    /// noise at `pos * frequency`.
+    fn fill(&self, area: Area, frequency: f64) -> Map<f32> {
+        let mut map = Map::new(area.size).with_offset(area.position);
+        map.fill_each(|p| self.get(p.x as f64 * frequency, p.y as f64 * frequency) as f32);
+        map
+    }
+}
+impl<N> Noise for &N
+where
+    N: Noise + ?Sized,
+{
+    fn get(&self, x: f64, y: f64) -> f64 {
+        (**self).get(x, y)
+    }
+}
+
+/// Hashes a lattice point, the same seed and point always give the same hash.
+fn hash(seed: u64, x: i64, y: i64) -> u64 {
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+/// A hash mapped to a value from 0 up to 1.
+fn unit(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// One of `n` unit vectors spread evenly around the circle, picked by `hash`.
+fn gradient(hash: u64, n: u64) -> (f64, f64) {
+    let angle = (hash % n) as f64 * std::f64::consts::TAU / n as f64;
+    (angle.cos(), angle.sin())
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Classic gradient noise, which is 0 at every integer position.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Perlin {
+    pub seed: u64,
+}
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Perlin { seed }
+    }
+}
+impl Noise for Perlin {
+    fn get(&self, x: f64, y: f64) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (ix, iy) = (x0 as i64, y0 as i64);
+        let corner = |cx: i64, cy: i64| {
+            let (gx, gy) = gradient(hash(self.seed, ix + cx, iy + cy), 8);
+            gx * (fx - cx as f64) + gy * (fy - cy as f64)
+        };
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+        // Unit gradients reach at most half the diagonal of a cell.
+        lerp(top, bottom, v) * std::f64::consts::SQRT_2
+    }
+}
+
+/// Gradient noise on a triangular lattice in the style of OpenSimplex2, with fewer directional
+/// artifacts than [Perlin].
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct OpenSimplex {
+    pub seed: u64,
+}
+impl OpenSimplex {
+    const SKEW: f64 = 0.366_025_403_784_438_6;
+    const UNSKEW: f64 = -0.211_324_865_405_187_13;
+    const RADIUS_SQUARED: f64 = 0.5;
+    // Scales the largest sum of the three corners found by sampling to 1.
+    const NORMALISE: f64 = 99.2;
+
+    pub fn new(seed: u64) -> Self {
+        OpenSimplex { seed }
+    }
+
+    fn corner(&self, cell: (i64, i64), dx: f64, dy: f64) -> f64 {
+        let falloff = Self::RADIUS_SQUARED - dx * dx - dy * dy;
+        if falloff <= 0.0 {
+            return 0.0;
+        }
+        let (gx, gy) = gradient(hash(self.seed, cell.0, cell.1), 24);
+        falloff.powi(4) * (gx * dx + gy * dy)
+    }
+}
+impl Noise for OpenSimplex {
+    fn get(&self, x: f64, y: f64) -> f64 {
+        let s = Self::SKEW * (x + y);
+        let (xs, ys) = (x + s, y + s);
+        let (xb, yb) = (xs.floor(), ys.floor());
+        let (xi, yi) = (xs - xb, ys - yb);
+        let (xb, yb) = (xb as i64, yb as i64);
+
+        let t = (xi + yi) * Self::UNSKEW;
+        let (dx0, dy0) = (xi + t, yi + t);
+        let opposite = 1.0 + 2.0 * Self::UNSKEW;
+        let mut value = self.corner((xb, yb), dx0, dy0);
+        value += self.corner((xb + 1, yb + 1), dx0 - opposite, dy0 - opposite);
+        value += if dy0 > dx0 {
+            self.corner((xb, yb + 1), dx0 - Self::UNSKEW, dy0 - Self::UNSKEW - 1.0)
+        } else {
+            self.corner((xb + 1, yb), dx0 - Self::UNSKEW - 1.0, dy0 - Self::UNSKEW)
+        };
+        (value * Self::NORMALISE).clamp(-1.0, 1.0)
+    }
+}
+
+/// Random values at every integer position, smoothly interpolated in between.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Value {
+    pub seed: u64,
+}
+impl Value {
+    pub fn new(seed: u64) -> Self {
+        Value { seed }
+    }
+}
+impl Noise for Value {
+    fn get(&self, x: f64, y: f64) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (ix, iy) = (x0 as i64, y0 as i64);
+        let corner = |cx: i64, cy: i64| unit(hash(self.seed, ix + cx, iy + cy)) * 2.0 - 1.0;
+        let (u, v) = (fade(x - x0), fade(y - y0));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+        lerp(top, bottom, v)
+    }
+}
+
+/// Which distance [Worley] noise returns.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub enum WorleyValue {
+    /// The distance to the nearest feature point.
+    Nearest,
+    /// The distance to the second nearest feature point.
+    SecondNearest,
+    /// The difference between both, which is 0 along the edges between cells.
+    Difference,
+}
+
+/// Cellular noise, the euclidean distance towards a random feature point in every integer cell.
+///
+/// Distances are measured in cells and are not centered around 0, [WorleyValue::Nearest] lies
+/// between 0 and about 1.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Worley {
+    pub seed: u64,
+    pub value: WorleyValue,
+}
+impl Worley {
+    pub fn new(seed: u64) -> Self {
+        Worley {
+            seed,
+            value: WorleyValue::Nearest,
+        }
+    }
+
+    pub fn with_value(mut self, value: WorleyValue) -> Self {
+        self.value = value;
+        self
+    }
+}
+impl Noise for Worley {
+    fn get(&self, x: f64, y: f64) -> f64 {
+        let (ix, iy) = (x.floor() as i64, y.floor() as i64);
+        let (mut nearest, mut second) = (f64::MAX, f64::MAX);
+        for cy in iy - 1..=iy + 1 {
+            for cx in ix - 1..=ix + 1 {
+                let h = hash(self.seed, cx, cy);
+                let px = cx as f64 + unit(h);
+                let py = cy as f64 + unit(hash(h, cx, cy));
+                let d = ((px - x).powi(2) + (py - y).powi(2)).sqrt();
+                if d < nearest {
+                    second = nearest;
+                    nearest = d;
+                } else if d < second {
+                    second = d;
+                }
+            }
+        }
+        match self.value {
+            WorleyValue::Nearest => nearest,
+            WorleyValue::SecondNearest => second,
+            WorleyValue::Difference => second - nearest,
+        }
+    }
+}
+
+/// How the octaves of a [Fractal] are combined.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub enum FractalKind {
+    /// Fractal brownian motion, the plain sum of all octaves.
+    Fbm,
+    /// Sharp ridges where the source crosses 0, for mountain ranges and rivers.
+    Ridged,
+    /// The sum of the absolute octaves, which gives billowy clouds and rolling hills.
+    Turbulence,
+}
+
+/// Sums several octaves of a noise source at increasing frequencies and decreasing amplitudes.
+///
+/// The result is scaled back to lie within -1 and 1 for sources which do.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Fractal<N> {
+    pub source: N,
+    pub kind: FractalKind,
+    pub octaves: usize,
+    pub lacunarity: f64,
+    pub gain: f64,
+}
+impl<N> Fractal<N>
+where
+    N: Noise,
+{
+    /// Creates a fractal of 4 octaves, which double in frequency and halve in amplitude.
+    pub fn new(source: N, kind: FractalKind) -> Self {
+        Fractal {
+            source,
+            kind,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    pub fn fbm(source: N) -> Self {
+        Fractal::new(source, FractalKind::Fbm)
+    }
+
+    pub fn ridged(source: N) -> Self {
+        Fractal::new(source, FractalKind::Ridged)
+    }
+
+    pub fn turbulence(source: N) -> Self {
+        Fractal::new(source, FractalKind::Turbulence)
+    }
+
+    pub fn with_octaves(mut self, octaves: usize) -> Self {
+        assert!(octaves > 0);
+        self.octaves = octaves;
+        self
+    }
+
+    /// The factor by which the frequency grows every octave.
+    pub fn with_lacunarity(mut self, lacunarity: f64) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    /// The factor by which the amplitude shrinks every octave.
+    pub fn with_gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+}
+impl<N> Noise for Fractal<N>
+where
+    N: Noise,
+{
+    fn get(&self, x: f64, y: f64) -> f64 {
+        let (mut sum, mut total) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        for octave in 0..self.octaves {
+            // Shifts every octave, so the lattices of the octaves do not line up at the origin.
+            let shift = octave as f64 * 19.19;
+            let n = self
+                .source
+                .get(x * frequency + shift, y * frequency - shift);
+            sum += amplitude
+                * match self.kind {
+                    FractalKind::Fbm => n,
+                    FractalKind::Ridged => (1.0 - n.abs()).powi(2),
+                    FractalKind::Turbulence => n.abs(),
+                };
+            total += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        match self.kind {
+            FractalKind::Fbm => sum / total,
+            FractalKind::Ridged | FractalKind::Turbulence => sum / total * 2.0 - 1.0,
+        }
+    }
+}
+
+/// Domain warping, samples `source` at a position moved by two samples of `warp`.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Warp<N, W> {
+    pub source: N,
+    pub warp: W,
+    pub strength: f64,
+}
+impl<N, W> Warp<N, W>
+where
+    N: Noise,
+    W: Noise,
+{
+    /// Moves every position by up to `strength` along both axes.
+    pub fn new(source: N, warp: W, strength: f64) -> Self {
+        Warp {
+            source,
+            warp,
+            strength,
+        }
+    }
+}
+impl<N, W> Noise for Warp<N, W>
+where
+    N: Noise,
+    W: Noise,
+{
+    fn get(&self, x: f64, y: f64) -> f64 {
+        let dx = self.warp.get(x, y);
+        let dy = self.warp.get(x + 5.2, y + 1.3);
+        self.source
+            .get(x + dx * self.strength, y + dy * self.strength)
+    }
+}
@@ -0,0 +1,343 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::slice::Iter;
+
+use crate::{Area, Coord, Distance, Map, MapMovement, MapObject, MovementCost};
+
+mod hpa;
+mod jps;
+
+pub use self::hpa::HierarchicalMap;
+
+/// The settings used by [Map::astar], [Map::bidirectional_astar] and [Map::jps].
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct PathConfig {
+    pub movement: MapMovement,
+    pub distance: Distance,
+    pub max_cost: Option<usize>,
+}
+impl PathConfig {
+    pub fn new() -> Self {
+        PathConfig {
+            movement: MapMovement::Both,
+            distance: Distance::Chebyshev,
+            max_cost: None,
+        }
+    }
+    pub fn with_movement(mut self, movement: MapMovement) -> Self {
+        self.movement = movement;
+        self
+    }
+    /// Sets how steps are weighed, which also serves as heuristic, defaults to
+    /// [Distance::Chebyshev].
+    ///
+    /// Path costs are measured in the units of [Distance::step_weights], so the default counts
+    /// every step as the cost of the tile entered.
+    pub fn with_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+    /// Paths which would cost more than `max_cost` are not searched for.
+    pub fn with_max_cost(mut self, max_cost: usize) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+    /// The cost of stepping from `from` onto a neighbouring tile costing `cost`.
+    pub(crate) fn step_cost(&self, from: Coord, to: Coord, cost: usize) -> usize {
+        let (orthogonal, diagonal) = self.distance.step_weights();
+        if from.x != to.x && from.y != to.y {
+            cost * diagonal
+        } else {
+            cost * orthogonal
+        }
+    }
+    fn within_budget(&self, cost: usize) -> bool {
+        self.max_cost.is_none_or(|max| cost <= max)
+    }
+}
+impl Default for PathConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A path between two tiles, including both the start and the goal.
+///
+/// Paths over a [crate::HexMap] hold [crate::HexCoord] positions instead of [Coord].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Path<P = Coord> {
+    coords: Vec<P>,
+    cost: usize,
+}
+impl<P> Path<P>
+where
+    P: Copy + PartialEq,
+{
+    pub fn new(coords: Vec<P>, cost: usize) -> Self {
+        assert!(!coords.is_empty());
+        Path { coords, cost }
+    }
+    pub fn start(&self) -> P {
+        self.coords[0]
+    }
+    pub fn goal(&self) -> P {
+        self.coords[self.coords.len() - 1]
+    }
+    /// The summed movement cost of all the tiles entered after the start, weighed as set with
+    /// [PathConfig::with_distance].
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+    /// The number of steps needed to reach the goal.
+    pub fn len(&self) -> usize {
+        self.coords.len() - 1
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the tile which follows `pos` on this path.
+    pub fn next_step(&self, pos: P) -> Option<P> {
+        let i = self.coords.iter().position(|c| *c == pos)?;
+        self.coords.get(i + 1).copied()
+    }
+    pub fn iter(&self) -> Iter<'_, P> {
+        self.coords.iter()
+    }
+    pub fn as_slice(&self) -> &[P] {
+        &self.coords
+    }
+}
+impl<'a, P> IntoIterator for &'a Path<P> {
+    type Item = &'a P;
+    type IntoIter = Iter<'a, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.coords.iter()
+    }
+}
+impl<P> IntoIterator for Path<P> {
+    type Item = P;
+    type IntoIter = std::vec::IntoIter<P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.coords.into_iter()
+    }
+}
+impl<P> From<Path<P>> for Vec<P> {
+    fn from(path: Path<P>) -> Self {
+        path.coords
+    }
+}
+
+fn unwind<P>(came_from: &HashMap<P, P>, mut current: P) -> Vec<P>
+where
+    P: Copy + Eq + Hash,
+{
+    let mut coords = vec![current];
+    while let Some(prev) = came_from.get(&current) {
+        coords.push(*prev);
+        current = *prev;
+    }
+    coords
+}
+
+/// A* over any grid, `neighbours` returns the positions reachable from a position together with
+/// the cost of stepping onto them, and `heuristic` estimates the remaining cost towards `goal`.
+pub(crate) fn astar_by<P, N, H>(
+    start: P,
+    goal: P,
+    mut neighbours: N,
+    heuristic: H,
+    max_cost: Option<usize>,
+) -> Option<Path<P>>
+where
+    P: Copy + Eq + Hash + Ord,
+    N: FnMut(P) -> Vec<(P, usize)>,
+    H: Fn(P) -> usize,
+{
+    if start == goal {
+        return Some(Path::new(vec![start], 0));
+    }
+
+    let mut queue = BinaryHeap::new();
+    let mut costs = HashMap::new();
+    let mut came_from = HashMap::new();
+
+    costs.insert(start, 0);
+    queue.push(Reverse((heuristic(start), 0, start)));
+
+    while let Some(Reverse((_, cost, current))) = queue.pop() {
+        if current == goal {
+            let mut coords = unwind(&came_from, current);
+            coords.reverse();
+            return Some(Path::new(coords, cost));
+        }
+        if cost > costs[&current] {
+            continue;
+        }
+
+        for (neighbour, step) in neighbours(current) {
+            let next = cost + step;
+            if max_cost.is_some_and(|max| next > max) {
+                continue;
+            }
+            if costs.get(&neighbour).is_none_or(|c| next < *c) {
+                costs.insert(neighbour, next);
+                came_from.insert(neighbour, current);
+                queue.push(Reverse((next + heuristic(neighbour), next, neighbour)));
+            }
+        }
+    }
+    None
+}
+
+impl<T> Map<T>
+where
+    T: MapObject,
+{
+    fn entry_cost(&self, pos: Coord) -> Option<usize> {
+        match self.get(pos)?.is_walkable() {
+            MovementCost::Possible(cost) => Some(cost),
+            MovementCost::Impossible => None,
+        }
+    }
+
+    /// Finds the cheapest path from `start` to `goal` using A*.
+    ///
+    /// The cost of a path is the sum of the [MovementCost] of every tile entered, weighed by
+    /// [Distance::step_weights]. The start tile itself does not have to be walkable.
+    pub fn astar(&self, start: Coord, goal: Coord, config: &PathConfig) -> Option<Path> {
+        self.astar_within(start, goal, config, &self.area)
+    }
+
+    /// A* which does not leave `bounds`.
+    fn astar_within(
+        &self,
+        start: Coord,
+        goal: Coord,
+        config: &PathConfig,
+        bounds: &Area,
+    ) -> Option<Path> {
+        let neighbours = |current: Coord| {
+            self.walkable_tiles(current, config.movement)
+                .into_iter()
+                .filter(|(neighbour, _)| bounds.point_within(*neighbour))
+                .map(|(neighbour, step)| (neighbour, config.step_cost(current, neighbour, step)))
+                .collect()
+        };
+        let heuristic = |pos: Coord| config.distance.estimate(pos, goal);
+        astar_by(start, goal, neighbours, heuristic, config.max_cost)
+    }
+
+    /// Finds the cheapest path from `start` to `goal` by searching from both ends at once.
+    ///
+    /// This finds a path of the same cost as [Map::astar], while expanding fewer tiles on large
+    /// maps.
+    pub fn bidirectional_astar(
+        &self,
+        start: Coord,
+        goal: Coord,
+        config: &PathConfig,
+    ) -> Option<Path> {
+        if start == goal {
+            return Some(Path::new(vec![start], 0));
+        }
+        self.entry_cost(goal)?;
+
+        let heuristic = config.distance;
+        let mut fwd_queue = BinaryHeap::new();
+        let mut bwd_queue = BinaryHeap::new();
+        let mut fwd_costs = HashMap::new();
+        let mut bwd_costs = HashMap::new();
+        let mut came_from = HashMap::new();
+        let mut leads_to = HashMap::new();
+        let mut best: Option<(usize, Coord)> = None;
+
+        // Forward costs include entering the tile itself, backward costs only the tiles after it.
+        fwd_costs.insert(start, 0);
+        bwd_costs.insert(goal, 0);
+        fwd_queue.push(Reverse((heuristic.estimate(start, goal), 0, start)));
+        bwd_queue.push(Reverse((heuristic.estimate(goal, start), 0, goal)));
+
+        loop {
+            let fwd_min = fwd_queue.peek().map(|Reverse((f, _, _))| *f);
+            let bwd_min = bwd_queue.peek().map(|Reverse((f, _, _))| *f);
+            let (fwd_min, bwd_min) = match (fwd_min, bwd_min) {
+                (Some(f), Some(b)) => (f, b),
+                _ => break,
+            };
+            if let Some((cost, _)) = best {
+                if fwd_min >= cost || bwd_min >= cost {
+                    break;
+                }
+            }
+
+            if fwd_min <= bwd_min {
+                let Reverse((_, cost, current)) = fwd_queue.pop().unwrap();
+                if cost > fwd_costs[&current] {
+                    continue;
+                }
+                for (neighbour, step) in self.walkable_tiles(current, config.movement) {
+                    let next = cost + config.step_cost(current, neighbour, step);
+                    if !config.within_budget(next) {
+                        continue;
+                    }
+                    if fwd_costs.get(&neighbour).is_none_or(|c| next < *c) {
+                        fwd_costs.insert(neighbour, next);
+                        came_from.insert(neighbour, current);
+                        if let Some(rest) = bwd_costs.get(&neighbour) {
+                            if best.is_none_or(|(b, _)| next + rest < b) {
+                                best = Some((next + rest, neighbour));
+                            }
+                        }
+                        let estimate = next + heuristic.estimate(neighbour, goal);
+                        fwd_queue.push(Reverse((estimate, next, neighbour)));
+                    }
+                }
+            } else {
+                let Reverse((_, cost, current)) = bwd_queue.pop().unwrap();
+                if cost > bwd_costs[&current] {
+                    continue;
+                }
+                let step = match self.entry_cost(current) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                for offset in config.movement.get_reachable_tiles() {
+                    let neighbour = current + offset;
+                    if !self.area.point_within(neighbour)
+                        || (neighbour != start && self.entry_cost(neighbour).is_none())
+                    {
+                        continue;
+                    }
+                    let next = cost + config.step_cost(neighbour, current, step);
+                    if !config.within_budget(next) {
+                        continue;
+                    }
+                    if bwd_costs.get(&neighbour).is_none_or(|c| next < *c) {
+                        bwd_costs.insert(neighbour, next);
+                        leads_to.insert(neighbour, current);
+                        if let Some(done) = fwd_costs.get(&neighbour) {
+                            if best.is_none_or(|(b, _)| next + done < b) {
+                                best = Some((next + done, neighbour));
+                            }
+                        }
+                        let estimate = next + heuristic.estimate(neighbour, start);
+                        bwd_queue.push(Reverse((estimate, next, neighbour)));
+                    }
+                }
+            }
+        }
+
+        let (cost, meet) = best?;
+        if !config.within_budget(cost) {
+            return None;
+        }
+        let mut coords = unwind(&came_from, meet);
+        coords.reverse();
+        coords.pop();
+        coords.extend(unwind(&leads_to, meet));
+        Some(Path::new(coords, cost))
+    }
+}
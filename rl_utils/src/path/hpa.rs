@@ -0,0 +1,459 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use super::{unwind, Path, PathConfig};
+use crate::{Area, Coord, Map, MapMovement, MapObject};
+
+/// A cached abstract graph for hierarchical path finding (HPA*) over large maps.
+///
+/// The map is split into clusters of `cluster_size`. Tiles where a path can cross from one cluster
+/// into its neighbour become entrances, and the costs between the entrances of each cluster are
+/// calculated once. A path is first planned over this graph, after which every abstract step is
+/// refined into tiles within a single cluster.
+///
+/// The resulting paths are close to, but not always, the cheapest path. When the walkability of
+/// a tile changes, [HierarchicalMap::update_tile] only rebuilds the clusters around that tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchicalMap {
+    area: Area,
+    cluster_size: Coord,
+    config: PathConfig,
+    borders: HashMap<(Coord, Coord), Vec<(Coord, Coord)>>,
+    edges: HashMap<Coord, Vec<(Coord, usize)>>,
+}
+impl HierarchicalMap {
+    pub fn new<T>(map: &Map<T>, cluster_size: Coord, config: PathConfig) -> Self
+    where
+        T: MapObject,
+    {
+        assert!(cluster_size.x > 0 && cluster_size.y > 0);
+
+        let mut hmap = HierarchicalMap {
+            area: map.area,
+            cluster_size,
+            config,
+            borders: HashMap::new(),
+            edges: HashMap::new(),
+        };
+        let clusters = hmap.clusters();
+        for c in &clusters {
+            hmap.build_borders(map, *c);
+        }
+        hmap.build_edges(map, &clusters);
+        hmap
+    }
+
+    pub fn cluster_size(&self) -> Coord {
+        self.cluster_size
+    }
+
+    /// The number of entrance tiles in the abstract graph.
+    pub fn node_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn cluster_count(&self) -> Coord {
+        Coord::new(
+            (self.area.size.x + self.cluster_size.x - 1) / self.cluster_size.x,
+            (self.area.size.y + self.cluster_size.y - 1) / self.cluster_size.y,
+        )
+    }
+
+    fn clusters(&self) -> Vec<Coord> {
+        let count = self.cluster_count();
+        let mut clusters = vec![];
+        for y in 0..count.y {
+            for x in 0..count.x {
+                clusters.push(Coord::new(x, y));
+            }
+        }
+        clusters
+    }
+
+    fn cluster_of(&self, pos: Coord) -> Coord {
+        let local = pos - self.area.position;
+        Coord::new(local.x / self.cluster_size.x, local.y / self.cluster_size.y)
+    }
+
+    fn cluster_area(&self, cluster: Coord) -> Option<Area> {
+        let count = self.cluster_count();
+        if cluster.x < 0 || cluster.y < 0 || cluster.x >= count.x || cluster.y >= count.y {
+            return None;
+        }
+        let position = Coord::new(
+            cluster.x * self.cluster_size.x,
+            cluster.y * self.cluster_size.y,
+        );
+        let size = Coord::new(
+            self.cluster_size.x.min(self.area.size.x - position.x),
+            self.cluster_size.y.min(self.area.size.y - position.y),
+        );
+        Some(Area::new(position + self.area.position, size))
+    }
+
+    fn has_diagonals(&self) -> bool {
+        self.config.movement != MapMovement::Orthogonal
+    }
+
+    fn has_orthogonals(&self) -> bool {
+        self.config.movement != MapMovement::Diagonal
+    }
+
+    /// Finds the entrances between `cluster` and its neighbours to the right and below it.
+    fn build_borders<T>(&mut self, map: &Map<T>, cluster: Coord)
+    where
+        T: MapObject,
+    {
+        let area = match self.cluster_area(cluster) {
+            Some(area) => area,
+            None => return,
+        };
+        let walkable = |p: Coord| map.area.point_within(p) && map.entry_cost(p).is_some();
+        let last = area.position + area.size - (1, 1).into();
+
+        for (offset, across, perpendicular) in [
+            (Coord::new(1, 0), Coord::new(1, 0), Coord::new(0, 1)),
+            (Coord::new(0, 1), Coord::new(0, 1), Coord::new(1, 0)),
+        ] {
+            let other = match self.cluster_area(cluster + offset) {
+                Some(other) => other,
+                None => continue,
+            };
+            let along: Vec<Coord> = if across.x != 0 {
+                (area.position.y..=last.y)
+                    .map(|y| Coord::new(last.x, y))
+                    .collect()
+            } else {
+                (area.position.x..=last.x)
+                    .map(|x| Coord::new(x, last.y))
+                    .collect()
+            };
+            let straight = |a: Coord| self.has_orthogonals() && walkable(a) && walkable(a + across);
+
+            // Every run of straight crossings becomes a single entrance, at its middle.
+            let mut entrances = vec![];
+            let mut run = vec![];
+            for a in &along {
+                if straight(*a) {
+                    run.push(*a);
+                    continue;
+                }
+                if let Some(mid) = run.get(run.len() / 2) {
+                    entrances.push((*mid, *mid + across));
+                }
+                run.clear();
+            }
+            if let Some(mid) = run.get(run.len() / 2) {
+                entrances.push((*mid, *mid + across));
+            }
+            if self.has_diagonals() {
+                for a in &along {
+                    for side in [perpendicular, Coord::new(0, 0) - perpendicular] {
+                        let b = *a + across + side;
+                        if other.point_within(b)
+                            && walkable(*a)
+                            && walkable(b)
+                            && !straight(*a)
+                            && !straight(*a + side)
+                        {
+                            entrances.push((*a, b));
+                        }
+                    }
+                }
+            }
+            self.borders.insert((cluster, cluster + offset), entrances);
+        }
+
+        if self.has_diagonals() {
+            for (offset, a) in [
+                (Coord::new(1, 1), last),
+                (Coord::new(-1, 1), Coord::new(area.position.x, last.y)),
+            ] {
+                if self.cluster_area(cluster + offset).is_none() {
+                    continue;
+                }
+                let b = a + offset;
+                let entrances = if walkable(a) && walkable(b) {
+                    vec![(a, b)]
+                } else {
+                    vec![]
+                };
+                self.borders.insert((cluster, cluster + offset), entrances);
+            }
+        }
+    }
+
+    /// Rebuilds the edges leaving the entrances of `clusters`.
+    fn build_edges<T>(&mut self, map: &Map<T>, clusters: &[Coord])
+    where
+        T: MapObject,
+    {
+        let rebuild: HashSet<Coord> = clusters.iter().copied().collect();
+        let cluster_size = self.cluster_size;
+        let origin = self.area.position;
+        let cluster_of = |p: Coord| {
+            let local = p - origin;
+            Coord::new(local.x / cluster_size.x, local.y / cluster_size.y)
+        };
+
+        self.edges
+            .retain(|node, _| !rebuild.contains(&cluster_of(*node)));
+
+        let mut nodes: HashMap<Coord, Vec<Coord>> = HashMap::new();
+        for entrances in self.borders.values() {
+            for (a, b) in entrances {
+                for (from, to) in [(*a, *b), (*b, *a)] {
+                    if !rebuild.contains(&cluster_of(from)) {
+                        continue;
+                    }
+                    let cost = match map.entry_cost(to) {
+                        Some(cost) => self.config.step_cost(from, to, cost),
+                        None => continue,
+                    };
+                    let edges = self.edges.entry(from).or_default();
+                    if !edges.contains(&(to, cost)) {
+                        edges.push((to, cost));
+                    }
+                    let cluster_nodes = nodes.entry(cluster_of(from)).or_default();
+                    if !cluster_nodes.contains(&from) {
+                        cluster_nodes.push(from);
+                    }
+                }
+            }
+        }
+
+        for (cluster, cluster_nodes) in nodes {
+            let area = match self.cluster_area(cluster) {
+                Some(area) => area,
+                None => continue,
+            };
+            for from in &cluster_nodes {
+                let costs = costs_within(map, *from, &area, &self.config);
+                for to in &cluster_nodes {
+                    if let Some(cost) = costs.get(to).filter(|_| from != to) {
+                        self.edges.get_mut(from).unwrap().push((*to, *cost));
+                    }
+                }
+            }
+        }
+        for (node, edges) in self.edges.iter_mut() {
+            if rebuild.contains(&cluster_of(*node)) {
+                edges.sort();
+            }
+        }
+    }
+
+    /// Updates the cached graph after the walkability or cost of `pos` has changed in `map`.
+    pub fn update_tile<T>(&mut self, map: &Map<T>, pos: Coord)
+    where
+        T: MapObject,
+    {
+        if !self.area.point_within(pos) {
+            return;
+        }
+        let cluster = self.cluster_of(pos);
+
+        let mut clusters = vec![];
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let c = cluster + (x, y).into();
+                if self.cluster_area(c).is_some() {
+                    clusters.push(c);
+                }
+            }
+        }
+        // Borders are stored with the cluster on the left or above, so those own the borders
+        // around `pos`.
+        for c in &clusters {
+            if c.y < cluster.y || (c.y == cluster.y && c.x <= cluster.x) {
+                self.build_borders(map, *c);
+            }
+        }
+        self.build_edges(map, &clusters);
+    }
+
+    /// Plans a path over the abstract graph, and refines it into a full path over `map`.
+    ///
+    /// `map` should be the map this graph was built from, including any updates passed on with
+    /// [HierarchicalMap::update_tile].
+    pub fn find_path<T>(&self, map: &Map<T>, start: Coord, goal: Coord) -> Option<Path>
+    where
+        T: MapObject,
+    {
+        if start == goal {
+            return Some(Path::new(vec![start], 0));
+        }
+        if !self.area.point_within(start) || !self.area.point_within(goal) {
+            return None;
+        }
+        let start_area = self.cluster_area(self.cluster_of(start))?;
+        let goal_cluster = self.cluster_of(goal);
+        let goal_area = self.cluster_area(goal_cluster)?;
+        map.entry_cost(goal)?;
+
+        if start_area == goal_area {
+            if let Some(path) = map.astar_within(start, goal, &self.config, &start_area) {
+                return Some(path);
+            }
+        }
+
+        // An unwalkable start is not connected to the entrances of its own cluster, so plan from
+        // each of its walkable neighbours instead.
+        if map.entry_cost(start).is_none() {
+            return map
+                .walkable_tiles(start, self.config.movement)
+                .into_iter()
+                .filter_map(|(n, cost)| {
+                    let path = self.find_path(map, n, goal)?;
+                    Some((self.config.step_cost(start, n, cost) + path.cost(), n, path))
+                })
+                .filter(|(cost, _, _)| self.config.within_budget(*cost))
+                .min_by_key(|(cost, n, _)| (*cost, *n))
+                .map(|(cost, _, path)| {
+                    let mut coords = vec![start];
+                    coords.extend(path);
+                    Path::new(coords, cost)
+                });
+        }
+
+        let from_start = costs_within(map, start, &start_area, &self.config);
+        let to_goal = costs_towards(map, goal, &goal_area, &self.config);
+
+        let neighbours = |node: Coord| {
+            let mut next = self.edges.get(&node).cloned().unwrap_or_default();
+            if node == start {
+                next.extend(
+                    from_start
+                        .iter()
+                        .filter(|(n, _)| self.edges.contains_key(n))
+                        .map(|(n, c)| (*n, *c)),
+                );
+            }
+            if let Some(cost) = to_goal.get(&node) {
+                next.push((goal, *cost));
+            }
+            next
+        };
+
+        let heuristic = self.config.distance;
+        let mut queue = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut came_from = HashMap::new();
+        costs.insert(start, 0);
+        queue.push(Reverse((heuristic.estimate(start, goal), 0, start)));
+
+        let mut found = false;
+        while let Some(Reverse((_, cost, current))) = queue.pop() {
+            if current == goal {
+                found = true;
+                break;
+            }
+            if cost > costs[&current] {
+                continue;
+            }
+            for (neighbour, step) in neighbours(current) {
+                let next = cost + step;
+                if !self.config.within_budget(next) {
+                    continue;
+                }
+                if costs.get(&neighbour).is_none_or(|c| next < *c) {
+                    costs.insert(neighbour, next);
+                    came_from.insert(neighbour, current);
+                    queue.push(Reverse((
+                        next + heuristic.estimate(neighbour, goal),
+                        next,
+                        neighbour,
+                    )));
+                }
+            }
+        }
+        if !found {
+            return None;
+        }
+
+        let mut abstract_path = unwind(&came_from, goal);
+        abstract_path.reverse();
+
+        let mut coords = vec![start];
+        let mut total = 0;
+        for pair in abstract_path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if self.cluster_of(from) != self.cluster_of(to) {
+                coords.push(to);
+                total += self.config.step_cost(from, to, map.entry_cost(to)?);
+            } else {
+                let area = self.cluster_area(self.cluster_of(from))?;
+                let segment = map.astar_within(from, to, &self.config, &area)?;
+                total += segment.cost();
+                coords.extend(segment.iter().skip(1));
+            }
+        }
+        Some(Path::new(coords, total))
+    }
+}
+
+/// The cost of reaching every tile within `bounds` from `from`.
+fn costs_within<T>(
+    map: &Map<T>,
+    from: Coord,
+    bounds: &Area,
+    config: &PathConfig,
+) -> HashMap<Coord, usize>
+where
+    T: MapObject,
+{
+    let mut queue = BinaryHeap::new();
+    let mut costs = HashMap::new();
+
+    costs.insert(from, 0);
+    queue.push(Reverse((0, from)));
+
+    while let Some(Reverse((cost, current))) = queue.pop() {
+        if cost > costs[&current] {
+            continue;
+        }
+        for (neighbour, step) in map.walkable_tiles(current, config.movement) {
+            let next = cost + config.step_cost(current, neighbour, step);
+            if bounds.point_within(neighbour) && costs.get(&neighbour).is_none_or(|c| next < *c) {
+                costs.insert(neighbour, next);
+                queue.push(Reverse((next, neighbour)));
+            }
+        }
+    }
+    costs
+}
+
+/// The cost of reaching `to` from every walkable tile within `bounds`.
+fn costs_towards<T>(
+    map: &Map<T>,
+    to: Coord,
+    bounds: &Area,
+    config: &PathConfig,
+) -> HashMap<Coord, usize>
+where
+    T: MapObject,
+{
+    let mut queue = BinaryHeap::new();
+    let mut costs = HashMap::new();
+
+    costs.insert(to, 0);
+    queue.push(Reverse((0, to)));
+
+    while let Some(Reverse((cost, current))) = queue.pop() {
+        if cost > costs[&current] {
+            continue;
+        }
+        let entry = match map.entry_cost(current) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        for (neighbour, _) in map.walkable_tiles(current, config.movement) {
+            let next = cost + config.step_cost(neighbour, current, entry);
+            if bounds.point_within(neighbour) && costs.get(&neighbour).is_none_or(|c| next < *c) {
+                costs.insert(neighbour, next);
+                queue.push(Reverse((next, neighbour)));
+            }
+        }
+    }
+    costs
+}
@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::{unwind, Path, PathConfig};
+use crate::{Coord, Map, MapMovement, MapObject, MovementCost};
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, 0),
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+impl<T> Map<T>
+where
+    T: MapObject,
+{
+    /// Returns the movement cost shared by every walkable tile, if there is such a cost.
+    pub fn uniform_cost(&self) -> Option<usize> {
+        let mut uniform = None;
+        for y in 0..self.area.size.y {
+            for x in 0..self.area.size.x {
+                let p = self.to_world((x, y).into());
+                if let MovementCost::Possible(cost) = self[p].is_walkable() {
+                    match uniform {
+                        None => uniform = Some(cost),
+                        Some(c) if c != cost => return None,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        uniform
+    }
+
+    fn passable(&self, pos: Coord) -> bool {
+        self.area.point_within(pos) && self.entry_cost(pos).is_some()
+    }
+
+    fn jump(&self, from: Coord, dir: Coord, goal: Coord) -> Option<Coord> {
+        let mut pos = from;
+        loop {
+            pos += dir;
+            if !self.passable(pos) {
+                return None;
+            }
+            if pos == goal {
+                return Some(pos);
+            }
+
+            let forced = if dir.x != 0 && dir.y != 0 {
+                (!self.passable(pos + (-dir.x, 0).into())
+                    && self.passable(pos + (-dir.x, dir.y).into()))
+                    || (!self.passable(pos + (0, -dir.y).into())
+                        && self.passable(pos + (dir.x, -dir.y).into()))
+            } else if dir.x != 0 {
+                (!self.passable(pos + (0, 1).into()) && self.passable(pos + (dir.x, 1).into()))
+                    || (!self.passable(pos + (0, -1).into())
+                        && self.passable(pos + (dir.x, -1).into()))
+            } else {
+                (!self.passable(pos + (1, 0).into()) && self.passable(pos + (1, dir.y).into()))
+                    || (!self.passable(pos + (-1, 0).into())
+                        && self.passable(pos + (-1, dir.y).into()))
+            };
+            if forced {
+                return Some(pos);
+            }
+
+            if dir.x != 0
+                && dir.y != 0
+                && (self.jump(pos, (dir.x, 0).into(), goal).is_some()
+                    || self.jump(pos, (0, dir.y).into(), goal).is_some())
+            {
+                return Some(pos);
+            }
+        }
+    }
+
+    /// The directions worth searching from `pos`, when it was reached from `parent`.
+    fn pruned_directions(&self, pos: Coord, parent: Option<&Coord>) -> Vec<Coord> {
+        let parent = match parent {
+            Some(parent) => *parent,
+            None => return DIRECTIONS.iter().map(|d| (*d).into()).collect(),
+        };
+        let d = Coord::new((pos.x - parent.x).signum(), (pos.y - parent.y).signum());
+
+        let mut dirs = vec![];
+        if d.x != 0 && d.y != 0 {
+            dirs.push((d.x, 0).into());
+            dirs.push((0, d.y).into());
+            dirs.push(d);
+            if !self.passable(pos + (-d.x, 0).into()) {
+                dirs.push((-d.x, d.y).into());
+            }
+            if !self.passable(pos + (0, -d.y).into()) {
+                dirs.push((d.x, -d.y).into());
+            }
+        } else if d.x != 0 {
+            dirs.push(d);
+            if !self.passable(pos + (0, 1).into()) {
+                dirs.push((d.x, 1).into());
+            }
+            if !self.passable(pos + (0, -1).into()) {
+                dirs.push((d.x, -1).into());
+            }
+        } else {
+            dirs.push(d);
+            if !self.passable(pos + (1, 0).into()) {
+                dirs.push((1, d.y).into());
+            }
+            if !self.passable(pos + (-1, 0).into()) {
+                dirs.push((-1, d.y).into());
+            }
+        }
+        dirs
+    }
+
+    /// Finds the cheapest path from `start` to `goal` using Jump Point Search.
+    ///
+    /// Jump Point Search only expands the tiles where the path may change direction, which is
+    /// much faster than [Map::astar] on large open maps. It requires [MapMovement::Both] and
+    /// every walkable tile to have the same [MovementCost]; when this is not the case,
+    /// [Map::astar] is used instead.
+    ///
+    /// Every call checks all tiles of the map with [Map::uniform_cost]. When searching the same
+    /// map many times, check it once and use [Map::jps_uniform] instead.
+    pub fn jps(&self, start: Coord, goal: Coord, config: &PathConfig) -> Option<Path> {
+        match self.uniform_cost() {
+            Some(cost) => self.jps_uniform(start, goal, cost, config),
+            None => self.astar(start, goal, config),
+        }
+    }
+
+    /// Like [Map::jps], for maps on which every walkable tile is known to cost `cost`.
+    ///
+    /// The tiles are not checked, paths on maps with other costs may not be the cheapest. Falls
+    /// back to [Map::astar] unless moving with [MapMovement::Both].
+    pub fn jps_uniform(
+        &self,
+        start: Coord,
+        goal: Coord,
+        cost: usize,
+        config: &PathConfig,
+    ) -> Option<Path> {
+        if config.movement != MapMovement::Both {
+            return self.astar(start, goal, config);
+        }
+        let step = cost;
+        if start == goal {
+            return Some(Path::new(vec![start], 0));
+        }
+
+        let mut queue = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut came_from = HashMap::new();
+
+        costs.insert(start, 0);
+        queue.push(Reverse((
+            config.distance.estimate(start, goal) * step,
+            0,
+            start,
+        )));
+
+        while let Some(Reverse((_, cost, current))) = queue.pop() {
+            if current == goal {
+                let jump_points = {
+                    let mut jp = unwind(&came_from, current);
+                    jp.reverse();
+                    jp
+                };
+
+                let mut coords = vec![start];
+                for pair in jump_points.windows(2) {
+                    let dir = Coord::new(
+                        (pair[1].x - pair[0].x).signum(),
+                        (pair[1].y - pair[0].y).signum(),
+                    );
+                    let mut pos = pair[0];
+                    while pos != pair[1] {
+                        pos += dir;
+                        coords.push(pos);
+                    }
+                }
+                return Some(Path::new(coords, cost));
+            }
+            if cost > costs[&current] {
+                continue;
+            }
+
+            for dir in self.pruned_directions(current, came_from.get(&current)) {
+                if let Some(jump_point) = self.jump(current, dir, goal) {
+                    // Jumps are straight lines, which the estimate measures exactly.
+                    let next = cost + config.distance.estimate(current, jump_point) * step;
+                    if !config.within_budget(next) {
+                        continue;
+                    }
+                    if costs.get(&jump_point).is_none_or(|c| next < *c) {
+                        costs.insert(jump_point, next);
+                        came_from.insert(jump_point, current);
+                        let estimate = next + config.distance.estimate(jump_point, goal) * step;
+                        queue.push(Reverse((estimate, next, jump_point)));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
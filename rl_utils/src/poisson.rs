@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::fmt::Debug;
+
+use crate::{Area, Coord, Map};
+
+/// A small seeded generator, so samples only depend on the seed.
+struct SplitMix(u64);
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value from 0 up to 1.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A value from 0 up to `n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Picks well spaced tiles with [Bridson's algorithm](https://www.cs.ubc.ca/~rbridson/docs/bridson-siggraph07-poissondisk.pdf),
+/// for spawn points, loot and vegetation which should not clump together.
+///
+/// Samples are at least `radius` apart, measured with euclidean distance between tile centers,
+/// and every allowed tile lies within `radius` of a sample. Regions which are not connected get
+/// samples as well.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct PoissonDisk {
+    pub area: Area,
+    pub radius: f64,
+    pub attempts: usize,
+    pub seed: u64,
+}
+impl PoissonDisk {
+    pub fn new(area: Area, radius: f64, seed: u64) -> Self {
+        assert!(radius > 0.0);
+        PoissonDisk {
+            area,
+            radius,
+            attempts: 30,
+            seed,
+        }
+    }
+
+    /// The number of candidates tried around every sample before giving up on it, defaults to 30.
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        assert!(attempts > 0);
+        self.attempts = attempts;
+        self
+    }
+
+    /// Samples all tiles of the area.
+    pub fn sample(&self) -> Vec<Coord> {
+        self.sample_by(|_| true, |_| self.radius)
+    }
+
+    /// Samples the tiles of both the area and `map` for which `allowed` returns true.
+    pub fn sample_map<T, F>(&self, map: &Map<T>, allowed: F) -> Vec<Coord>
+    where
+        T: Debug + Clone + PartialEq,
+        F: Fn(&T) -> bool,
+    {
+        self.sample_by(|p| map.get(p).is_some_and(&allowed), |_| self.radius)
+    }
+
+    /// Samples the tiles of the area for which `allowed` returns true, with a radius per tile.
+    ///
+    /// Two samples are at least the larger of their radii apart, radii smaller than `radius` are
+    /// raised to it.
+    pub fn sample_by<A, R>(&self, allowed: A, radius: R) -> Vec<Coord>
+    where
+        A: Fn(Coord) -> bool,
+        R: Fn(Coord) -> f64,
+    {
+        let mut rng = SplitMix(self.seed);
+        let radius = |p: Coord| radius(p).max(self.radius);
+        let allowed = |p: Coord| self.area.point_within(p) && allowed(p);
+
+        // Every cell is `radius` wide, so only the cells around a tile have to be searched.
+        let cell_size = self.radius;
+        let cell = |p: Coord| {
+            Coord::new(
+                (p.x as f64 / cell_size).floor() as isize,
+                (p.y as f64 / cell_size).floor() as isize,
+            )
+        };
+        let mut grid: HashMap<Coord, Vec<(Coord, f64)>> = HashMap::new();
+        let mut largest = self.radius;
+        let mut samples = vec![];
+
+        let mut fits = |p: Coord, r: f64, grid: &HashMap<Coord, Vec<(Coord, f64)>>| {
+            let reach = (r.max(largest) / cell_size).ceil() as isize;
+            let c = cell(p);
+            for y in c.y - reach..=c.y + reach {
+                for x in c.x - reach..=c.x + reach {
+                    for (q, qr) in grid.get(&Coord::new(x, y)).into_iter().flatten() {
+                        let d = *q - p;
+                        let min = r.max(*qr);
+                        if ((d.x * d.x + d.y * d.y) as f64) < min * min {
+                            return false;
+                        }
+                    }
+                }
+            }
+            largest = largest.max(r);
+            true
+        };
+
+        // Every allowed tile is a possible starting point, in random order, so every region is
+        // filled.
+        let mut starts: Vec<Coord> = self.area.iter().filter(|p| allowed(*p)).collect();
+        for i in (1..starts.len()).rev() {
+            starts.swap(i, rng.below(i + 1));
+        }
+
+        for start in starts {
+            let r = radius(start);
+            if !fits(start, r, &grid) {
+                continue;
+            }
+            grid.entry(cell(start)).or_default().push((start, r));
+            samples.push(start);
+
+            let mut active = vec![(start, r)];
+            while !active.is_empty() {
+                let i = rng.below(active.len());
+                let (p, r) = active[i];
+                let mut found = false;
+                for _ in 0..self.attempts {
+                    let angle = rng.unit() * TAU;
+                    let distance = r * (1.0 + rng.unit());
+                    let candidate = p + Coord::new(
+                        (angle.cos() * distance).round() as isize,
+                        (angle.sin() * distance).round() as isize,
+                    );
+                    if !allowed(candidate) {
+                        continue;
+                    }
+                    let cr = radius(candidate);
+                    if fits(candidate, cr, &grid) {
+                        grid.entry(cell(candidate))
+                            .or_default()
+                            .push((candidate, cr));
+                        samples.push(candidate);
+                        active.push((candidate, cr));
+                        found = true;
+                        break;
+                    }
+                }
+                if !found {
+                    active.swap_remove(i);
+                }
+            }
+        }
+        samples
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{Coord, Map, MapMovement, MapObject, MovementCost};
+
+/// The connected regions of walkable tiles in a map, see [Map::regions].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regions {
+    /// The region id of every tile, or None when the tile is not walkable.
+    pub labels: Map<Option<usize>>,
+    sizes: Vec<usize>,
+    movement: MapMovement,
+}
+impl Regions {
+    pub fn region(&self, pos: Coord) -> Option<usize> {
+        if !self.labels.area.point_within(pos) {
+            return None;
+        }
+        self.labels[pos]
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// The number of tiles in every region, indexed by region id.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// The region with the most tiles.
+    pub fn largest(&self) -> Option<usize> {
+        (0..self.sizes.len()).max_by_key(|r| (self.sizes[*r], usize::MAX - r))
+    }
+
+    /// Returns which regions are separated by a single unwalkable tile, together with the tiles
+    /// which separate them.
+    ///
+    /// These tiles are candidates for doors or tunnels connecting the regions.
+    pub fn adjacency(&self) -> BTreeMap<(usize, usize), Vec<Coord>> {
+        let mut adjacent: BTreeMap<(usize, usize), Vec<Coord>> = BTreeMap::new();
+
+        for y in 0..self.labels.area.size.y {
+            for x in 0..self.labels.area.size.x {
+                let p = self.labels.to_world((x, y).into());
+                if self.labels[p].is_some() {
+                    continue;
+                }
+
+                let mut touching = vec![];
+                for offset in self.movement.get_reachable_tiles() {
+                    if let Some(r) = self.region(p + offset) {
+                        if !touching.contains(&r) {
+                            touching.push(r);
+                        }
+                    }
+                }
+                touching.sort_unstable();
+                for (i, a) in touching.iter().enumerate() {
+                    for b in &touching[i + 1..] {
+                        adjacent.entry((*a, *b)).or_default().push(p);
+                    }
+                }
+            }
+        }
+        adjacent
+    }
+}
+
+impl<T> Map<T>
+where
+    T: MapObject,
+{
+    /// Labels every group of walkable tiles which can reach each other using `movement`.
+    ///
+    /// Region ids are handed out in the order the regions are found, scanning the map row by row.
+    pub fn regions(&self, movement: MapMovement) -> Regions {
+        let mut labels = Map::new(self.area.size).with_offset(self.area.position);
+        labels.fill(None);
+        let mut sizes = vec![];
+
+        for y in 0..self.area.size.y {
+            for x in 0..self.area.size.x {
+                let p = self.to_world((x, y).into());
+                if labels[p].is_some() || self[p].is_walkable() == MovementCost::Impossible {
+                    continue;
+                }
+
+                let region = sizes.len();
+                let mut size = 1;
+                let mut queue = VecDeque::new();
+                labels[p] = Some(region);
+                queue.push_back(p);
+
+                while let Some(current) = queue.pop_front() {
+                    for (neighbour, _) in self.walkable_tiles(current, movement) {
+                        if labels[neighbour].is_none() {
+                            labels[neighbour] = Some(region);
+                            size += 1;
+                            queue.push_back(neighbour);
+                        }
+                    }
+                }
+                sizes.push(size);
+            }
+        }
+
+        Regions {
+            labels,
+            sizes,
+            movement,
+        }
+    }
+
+    /// Returns the walkable tiles which would split their region in two when blocked.
+    ///
+    /// These are single tile chokepoints, such as the tiles of a one tile wide corridor.
+    pub fn articulation_tiles(&self, movement: MapMovement) -> Vec<Coord> {
+        self.chokepoints(movement).0
+    }
+
+    /// Returns the steps between two walkable tiles which are the only way to move between
+    /// the parts of their region.
+    pub fn bridges(&self, movement: MapMovement) -> Vec<(Coord, Coord)> {
+        self.chokepoints(movement).1
+    }
+
+    /// Finds the articulation tiles and bridges with an iterative version of Tarjan's algorithm.
+    fn chokepoints(&self, movement: MapMovement) -> (Vec<Coord>, Vec<(Coord, Coord)>) {
+        const NONE: usize = usize::MAX;
+
+        let width = self.area.size.x;
+        let index = |p: Coord| {
+            let local = self.to_local(p);
+            (local.y * width + local.x) as usize
+        };
+        let coord = |i: usize| self.to_world(Coord::new(i as isize % width, i as isize / width));
+        let neighbours = |i: usize| -> Vec<usize> {
+            self.walkable_tiles(coord(i), movement)
+                .into_iter()
+                .map(|(n, _)| index(n))
+                .collect()
+        };
+
+        let tiles = (self.area.size.x * self.area.size.y) as usize;
+        let mut discovered = vec![NONE; tiles];
+        let mut low = vec![NONE; tiles];
+        let mut is_articulation = vec![false; tiles];
+        let mut bridges = vec![];
+        let mut time = 0;
+
+        for root in 0..tiles {
+            if discovered[root] != NONE
+                || self[coord(root)].is_walkable() == MovementCost::Impossible
+            {
+                continue;
+            }
+
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            let mut stack = vec![(root, NONE, neighbours(root), 0)];
+
+            while let Some((node, parent, next, i)) = stack.last_mut() {
+                let node = *node;
+                let parent = *parent;
+                if *i < next.len() {
+                    let n = next[*i];
+                    *i += 1;
+                    if discovered[n] == NONE {
+                        discovered[n] = time;
+                        low[n] = time;
+                        time += 1;
+                        if node == root {
+                            root_children += 1;
+                        }
+                        stack.push((n, node, neighbours(n), 0));
+                    } else if n != parent {
+                        low[node] = low[node].min(discovered[n]);
+                    }
+                } else {
+                    stack.pop();
+                    if parent != NONE {
+                        low[parent] = low[parent].min(low[node]);
+                        if parent != root && low[node] >= discovered[parent] {
+                            is_articulation[parent] = true;
+                        }
+                        if low[node] > discovered[parent] {
+                            let (a, b) = (coord(parent), coord(node));
+                            bridges.push((a.min(b), a.max(b)));
+                        }
+                    }
+                }
+            }
+            if root_children > 1 {
+                is_articulation[root] = true;
+            }
+        }
+
+        let articulation = (0..tiles)
+            .filter(|i| is_articulation[*i])
+            .map(coord)
+            .collect();
+        bridges.sort_unstable_by_key(|(a, b)| (a.y, a.x, b.y, b.x));
+        (articulation, bridges)
+    }
+}
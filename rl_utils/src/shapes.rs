@@ -0,0 +1,102 @@
+use std::iter;
+
+use crate::{Area, Coord, Distance};
+
+/// A filled or outlined ellipse of tiles, for area of effect and explosion templates.
+///
+/// Uses the same radius rules as the vision shapes of rl_fov: by default a tile at offset
+/// `(x, y)` of a circle is inside when `x² + y² <= r² + r`, which gives rounder circles for small
+/// radii, a strict ellipse uses `x² + y² <= r²`.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Ellipse {
+    pub center: Coord,
+    pub radii: Coord,
+    pub strict: bool,
+}
+impl Ellipse {
+    /// Creates an ellipse reaching `radii.x` tiles to the left and right of the center, and
+    /// `radii.y` tiles above and below it.
+    pub fn new(center: Coord, radii: Coord) -> Self {
+        assert!(radii.x >= 0 && radii.y >= 0);
+        Ellipse {
+            center,
+            radii,
+            strict: false,
+        }
+    }
+
+    pub fn circle(center: Coord, radius: isize) -> Self {
+        Ellipse::new(center, (radius, radius).into())
+    }
+
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// The smallest area containing the ellipse.
+    pub fn bounds(&self) -> Area {
+        Area::new(
+            self.center - self.radii,
+            (2 * self.radii.x + 1, 2 * self.radii.y + 1).into(),
+        )
+    }
+
+    pub fn contains(&self, pos: Coord) -> bool {
+        let Coord { x: dx, y: dy } = pos.delta_abs(self.center);
+        let Coord { x: rx, y: ry } = self.radii;
+        if dx > rx || dy > ry {
+            return false;
+        }
+        // The radius rule of a circle, scaled along both axes.
+        let (sx, sy) = if self.strict {
+            (rx * rx, ry * ry)
+        } else {
+            (rx * rx + rx, ry * ry + ry)
+        };
+        dx * dx * sy + dy * dy * sx <= sx * sy
+    }
+
+    /// Iterates over all tiles of the ellipse, row by row.
+    pub fn filled(&self) -> impl Iterator<Item = Coord> {
+        let ellipse = *self;
+        self.bounds().iter().filter(move |p| ellipse.contains(*p))
+    }
+
+    /// Iterates over the tiles of the ellipse next to a tile outside of it, row by row.
+    ///
+    /// The outline is connected through diagonal steps.
+    pub fn outline(&self) -> impl Iterator<Item = Coord> {
+        let ellipse = *self;
+        self.filled().filter(move |p| {
+            [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .any(|d| !ellipse.contains(*p + (*d).into()))
+        })
+    }
+}
+
+/// Iterates over the tiles around `center` in a square spiral, starting with the center itself.
+///
+/// Every ring is walked clockwise from its top left corner, see [Area::perimeter], so the first
+/// `(2r + 1)²` tiles are the square of radius `r`. The spiral never ends, limit it with
+/// [Iterator::take] or [Iterator::take_while].
+pub fn spiral(center: Coord) -> impl Iterator<Item = Coord> {
+    iter::once(center).chain((1..).flat_map(move |r: isize| {
+        Area::new(center - (r, r).into(), (2 * r + 1, 2 * r + 1).into()).perimeter()
+    }))
+}
+
+/// Iterates over the tiles at `radius` from `center` measured with `metric`, row by row.
+///
+/// Distances are rounded to the nearest integer like [crate::Map::distance_transform], so the
+/// rings of increasing radii cover every tile exactly once.
+pub fn ring(center: Coord, radius: isize, metric: Distance) -> impl Iterator<Item = Coord> {
+    let bounds = Area::new(
+        center - (radius, radius).into(),
+        (2 * radius + 1, 2 * radius + 1).into(),
+    );
+    bounds
+        .iter()
+        .filter(move |p| metric.measure(center, *p).round() as isize == radius)
+}
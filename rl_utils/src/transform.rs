@@ -0,0 +1,112 @@
+use std::fmt::Debug;
+
+use crate::{Area, Coord, Map};
+
+/// A clockwise rotation in steps of 90 degrees.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd)]
+pub enum Rotation {
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+/// Transforms which return a new map keep `area.position`, so the top left tile of the result
+/// lies at the same world position as the top left tile of the original.
+impl<T> Map<T>
+where
+    T: Debug + Clone + PartialEq,
+{
+    /// Builds a map of `size` at the same position, taking every tile from the local position
+    /// returned by `source`.
+    fn remap<F>(&self, size: Coord, source: F) -> Map<T>
+    where
+        F: Fn(Coord) -> Coord,
+    {
+        let mut data = Vec::with_capacity((size.x * size.y) as usize);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                data.push(self.get_local(source((x, y).into())).unwrap().clone());
+            }
+        }
+        Map {
+            area: Area::new(self.area.position, size),
+            data,
+        }
+    }
+
+    pub fn rotate(&self, rotation: Rotation) -> Map<T> {
+        let Coord { x: w, y: h } = self.area.size;
+        match rotation {
+            Rotation::Clockwise90 => self.remap((h, w).into(), |p| (p.y, h - 1 - p.x).into()),
+            Rotation::Clockwise180 => {
+                self.remap((w, h).into(), |p| (w - 1 - p.x, h - 1 - p.y).into())
+            }
+            Rotation::Clockwise270 => self.remap((h, w).into(), |p| (w - 1 - p.y, p.x).into()),
+        }
+    }
+
+    /// Mirrors the map left to right.
+    pub fn mirror_horizontal(&self) -> Map<T> {
+        let w = self.area.size.x;
+        self.remap(self.area.size, |p| (w - 1 - p.x, p.y).into())
+    }
+
+    /// Mirrors the map top to bottom.
+    pub fn mirror_vertical(&self) -> Map<T> {
+        let h = self.area.size.y;
+        self.remap(self.area.size, |p| (p.x, h - 1 - p.y).into())
+    }
+
+    /// Swaps rows and columns, mirroring the map along the diagonal from its top left tile.
+    pub fn transpose(&self) -> Map<T> {
+        let size = self.area.size;
+        self.remap((size.y, size.x).into(), |p| (p.y, p.x).into())
+    }
+
+    /// Returns the tiles within `area` as a map at `area.position`, or None when `area` does not
+    /// lie within this map.
+    pub fn crop(&self, area: Area) -> Option<Map<T>> {
+        if !self.area.area_within(area) {
+            return None;
+        }
+        let local = self.to_local(area.position);
+        let mut cropped = self.remap(area.size, |p| p + local);
+        cropped.area.position = area.position;
+        Some(cropped)
+    }
+
+    /// Draws `other` with its top left tile at the world position `at`.
+    ///
+    /// Every covered tile is replaced by the result of `merge`, which receives the current tile
+    /// and the tile of `other`. Tiles of `other` which fall outside of this map are skipped.
+    pub fn blit<U, F>(&mut self, other: &Map<U>, at: Coord, mut merge: F)
+    where
+        U: Debug + Clone + PartialEq,
+        F: FnMut(&T, &U) -> T,
+    {
+        for y in 0..other.area.size.y {
+            for x in 0..other.area.size.x {
+                let local: Coord = (x, y).into();
+                if let Some(tile) = self.get_mut(at + local) {
+                    *tile = merge(tile, other.get_local(local).unwrap());
+                }
+            }
+        }
+    }
+
+    /// Changes the area of the map.
+    ///
+    /// Tiles keep their world position, tiles outside of `area` are dropped and new tiles are set
+    /// to `fill`.
+    pub fn resize(&mut self, area: Area, fill: T) {
+        let mut data = Vec::with_capacity((area.size.x * area.size.y) as usize);
+        for y in 0..area.size.y {
+            for x in 0..area.size.x {
+                let tile = self.get(area.position + (x, y).into());
+                data.push(tile.unwrap_or(&fill).clone());
+            }
+        }
+        self.area = area;
+        self.data = data;
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use rl_utils::{Area, Coord};
+
+fn area() -> impl Strategy<Value = Area> {
+    (-6isize..6, -6isize..6, 0isize..7, 0isize..7)
+        .prop_map(|(x, y, w, h)| Area::new((x, y).into(), (w, h).into()))
+}
+
+fn tiles(area: Area) -> BTreeSet<Coord> {
+    area.iter().collect()
+}
+
+#[test]
+fn iteration_matches_containment() {
+    let area = Area::new((2, -1).into(), (3, 2).into());
+    assert_eq!(
+        area.iter().collect::<Vec<_>>(),
+        vec![
+            Coord::new(2, -1),
+            Coord::new(3, -1),
+            Coord::new(4, -1),
+            Coord::new(2, 0),
+            Coord::new(3, 0),
+            Coord::new(4, 0),
+        ]
+    );
+    assert_eq!(Area::new((0, 0).into(), (0, 5).into()).iter().count(), 0);
+}
+
+#[test]
+fn overlapping_wide_and_tall_areas() {
+    // A wide and a tall area crossing each other, without any corner inside the other area.
+    let wide = Area::new((0, 4).into(), (10, 2).into());
+    let tall = Area::new((4, 0).into(), (2, 10).into());
+    assert!(wide.overlaps(tall));
+    assert!(tall.overlaps(wide));
+    assert_eq!(
+        wide.intersect(tall),
+        Some(Area::new((4, 4).into(), (2, 2).into()))
+    );
+    assert!(!wide.overlaps(Area::new((10, 4).into(), (2, 2).into())));
+}
+
+#[test]
+fn splitting_and_borders() {
+    let area = Area::new((1, 2).into(), (7, 5).into());
+    assert_eq!(
+        area.split_x(3),
+        Some((
+            Area::new((1, 2).into(), (3, 5).into()),
+            Area::new((4, 2).into(), (4, 5).into())
+        ))
+    );
+    assert_eq!(area.split_y(5), None);
+    assert_eq!(
+        area.split_grid(2, 2),
+        vec![
+            Area::new((1, 2).into(), (4, 3).into()),
+            Area::new((5, 2).into(), (3, 3).into()),
+            Area::new((1, 5).into(), (4, 2).into()),
+            Area::new((5, 5).into(), (3, 2).into()),
+        ]
+    );
+    assert_eq!(area.inset(1), Some(Area::new((2, 3).into(), (5, 3).into())));
+    assert_eq!(area.inset(3), None);
+    assert_eq!(area.outset(1).inset(1), Some(area));
+    assert_eq!(
+        area.corners(),
+        [
+            Coord::new(1, 2),
+            Coord::new(7, 2),
+            Coord::new(7, 6),
+            Coord::new(1, 6)
+        ]
+    );
+    let small = Area::new((0, 0).into(), (3, 2).into());
+    assert_eq!(
+        small.perimeter().collect::<Vec<_>>(),
+        vec![
+            Coord::new(0, 0),
+            Coord::new(1, 0),
+            Coord::new(2, 0),
+            Coord::new(2, 1),
+            Coord::new(1, 1),
+            Coord::new(0, 1),
+        ]
+    );
+}
+
+proptest! {
+    #[test]
+    fn operations_match_tile_sets(a in area(), b in area(), columns in 1isize..4, rows in 1isize..4) {
+        let (ta, tb) = (tiles(a), tiles(b));
+        prop_assert_eq!(ta.len() as isize, a.size.x * a.size.y);
+        for t in &ta {
+            prop_assert!(a.point_within(*t));
+        }
+
+        let shared: BTreeSet<Coord> = ta.intersection(&tb).copied().collect();
+        prop_assert_eq!(a.overlaps(b), !shared.is_empty());
+        prop_assert_eq!(a.intersect(b).map(tiles).unwrap_or_default(), shared);
+
+        if !a.is_empty() && !b.is_empty() {
+            let union = a.union(b);
+            prop_assert!(union.area_within(a) && union.area_within(b));
+        }
+
+        let border: Vec<Coord> = a.perimeter().collect();
+        let expected: BTreeSet<Coord> = ta
+            .iter()
+            .filter(|t| a.inset(1).is_none_or(|inner| !inner.point_within(**t)))
+            .copied()
+            .collect();
+        prop_assert_eq!(border.len(), expected.len());
+        prop_assert_eq!(border.into_iter().collect::<BTreeSet<_>>(), expected);
+
+        if a.size.x >= columns && a.size.y >= rows {
+            let cells = a.split_grid(columns, rows);
+            let mut covered = BTreeSet::new();
+            for cell in &cells {
+                prop_assert!(!cell.is_empty());
+                for t in tiles(*cell) {
+                    prop_assert!(covered.insert(t));
+                }
+            }
+            prop_assert_eq!(covered, ta);
+        }
+    }
+}
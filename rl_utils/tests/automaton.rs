@@ -0,0 +1,147 @@
+use proptest::prelude::*;
+use rl_utils::{
+    Automaton, Boundary, Coord, LifeRule, Map, Neighbourhood, OffsetLayout, ParseRuleError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Off,
+    On,
+    Dying,
+}
+
+fn cells(size: Coord, on: &[(isize, isize)]) -> Map<Cell> {
+    let mut map = Map::new(size);
+    map.fill(Cell::Off);
+    for p in on {
+        map[*p] = Cell::On;
+    }
+    map
+}
+
+fn life() -> Automaton<Cell> {
+    let rule = "B3/S23".parse().unwrap();
+    Automaton::life(rule, Neighbourhood::Moore(1), Cell::On, Cell::Off)
+}
+
+#[test]
+fn parse_rules() {
+    let rule: LifeRule = "B5678/S45678".parse().unwrap();
+    assert_eq!(rule, LifeRule::new(&[5, 6, 7, 8], &[4, 5, 6, 7, 8]));
+    assert_eq!(rule.to_string(), "B5678/S45678");
+    assert_eq!("s23/b3".parse(), Ok(LifeRule::new(&[3], &[2, 3])));
+    assert_eq!("B/S".parse(), Ok(LifeRule::new(&[], &[])));
+
+    assert_eq!(
+        "B3".parse::<LifeRule>(),
+        Err(ParseRuleError::MissingSurvival)
+    );
+    assert_eq!("S23".parse::<LifeRule>(), Err(ParseRuleError::MissingBirth));
+    assert_eq!(
+        "B3/S2x".parse::<LifeRule>(),
+        Err(ParseRuleError::InvalidCount('x'))
+    );
+    assert_eq!(
+        "B3/B2".parse::<LifeRule>(),
+        Err(ParseRuleError::InvalidPart("B2".to_string()))
+    );
+}
+
+#[test]
+fn neighbourhoods() {
+    let pos = Coord::new(4, 5);
+    assert_eq!(Neighbourhood::Moore(1).around(pos).len(), 8);
+    assert_eq!(Neighbourhood::Moore(2).around(pos).len(), 24);
+    assert_eq!(Neighbourhood::VonNeumann(1).around(pos).len(), 4);
+    assert_eq!(Neighbourhood::VonNeumann(2).around(pos).len(), 12);
+    for r in 1..4 {
+        let around = Neighbourhood::VonNeumann(r).around(pos);
+        assert!(around.iter().all(|p| p.delta_abs_total(pos) <= r));
+        assert!(!around.contains(&pos));
+    }
+    for layout in [OffsetLayout::OddR, OffsetLayout::EvenQ] {
+        assert_eq!(Neighbourhood::Hex(layout, 1).around(pos).len(), 6);
+        assert_eq!(Neighbourhood::Hex(layout, 2).around(pos).len(), 18);
+    }
+}
+
+#[test]
+fn blinker_and_glider() {
+    let blinker = cells((5, 5).into(), &[(1, 2), (2, 2), (3, 2)]);
+    let walled = life().with_boundary(Boundary::Wall(Cell::Off));
+    assert_eq!(
+        walled.step(&blinker),
+        cells((5, 5).into(), &[(2, 1), (2, 2), (2, 3)])
+    );
+    assert_eq!(walled.run(&blinker, 2), blinker);
+
+    // A glider moves one tile diagonally every four generations, and wraps around the edges.
+    let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    let start = cells((6, 6).into(), &glider);
+    let wrapped = life().with_boundary(Boundary::Wrap);
+    let moved: Vec<_> = glider.iter().map(|(x, y)| (x + 1, y + 1)).collect();
+    assert_eq!(wrapped.run(&start, 4), cells((6, 6).into(), &moved));
+    assert_eq!(wrapped.run(&start, 24), start);
+}
+
+#[test]
+fn boundaries() {
+    // Walls fill the border of an empty map with the cave rule, mirroring keeps it empty.
+    let empty = cells((6, 6).into(), &[]);
+    let caves = Automaton::life(
+        "B5678/S45678".parse().unwrap(),
+        Neighbourhood::Moore(1),
+        Cell::On,
+        Cell::Off,
+    );
+    assert_eq!(caves.step(&empty), empty);
+    let walled = caves.with_boundary(Boundary::Wall(Cell::On)).step(&empty);
+    assert_eq!(walled[Coord::new(0, 0)], Cell::On);
+    assert_eq!(walled[Coord::new(0, 3)], Cell::Off);
+
+    let map = cells((3, 3).into(), &[(0, 0)]).with_offset((10, 20).into());
+    let mirror = Automaton::new(Neighbourhood::Moore(1), |c: &Cell, _: &[Cell]| *c);
+    let around = mirror.neighbours(&map, (10, 20).into());
+    assert_eq!(around.iter().filter(|c| **c == Cell::On).count(), 3);
+}
+
+#[test]
+fn multiple_states() {
+    // Brian's Brain: cells are born with two living neighbours, and always die over one step.
+    let brain = Automaton::new(
+        Neighbourhood::Moore(1),
+        |cell: &Cell, around: &[Cell]| match cell {
+            Cell::On => Cell::Dying,
+            Cell::Dying => Cell::Off,
+            Cell::Off if around.iter().filter(|c| **c == Cell::On).count() == 2 => Cell::On,
+            Cell::Off => Cell::Off,
+        },
+    )
+    .with_boundary(Boundary::Wall(Cell::Off));
+    let start = cells((4, 4).into(), &[(1, 1), (2, 1)]);
+    let next = brain.step(&start);
+    assert_eq!(next[Coord::new(1, 1)], Cell::Dying);
+    assert_eq!(next[Coord::new(1, 0)], Cell::On);
+    assert_eq!(next[Coord::new(2, 2)], Cell::On);
+    assert_eq!(brain.step(&next)[Coord::new(1, 1)], Cell::Off);
+}
+
+proptest! {
+    #[test]
+    fn wrapping_is_shift_invariant(
+        on in prop::collection::vec((0isize..8, 0isize..8), 0..30),
+        shift in (0isize..8, 0isize..8),
+    ) {
+        let size = Coord::new(8, 8);
+        let shifted: Vec<_> =
+            on.iter().map(|(x, y)| ((x + shift.0) % 8, (y + shift.1) % 8)).collect();
+        let wrapped = life().with_boundary(Boundary::Wrap);
+        let a = wrapped.step(&cells(size, &on));
+        let b = wrapped.step(&cells(size, &shifted));
+        for y in 0..8 {
+            for x in 0..8 {
+                prop_assert_eq!(a[(x, y)], b[((x + shift.0) % 8, (y + shift.1) % 8)]);
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeSet;
+
+mod common;
+
+use common::Tile;
+use proptest::prelude::*;
+use rl_utils::{BitMap, Coord, Map};
+
+fn bitmap(size: Coord, offset: Coord, bits: &[bool]) -> BitMap {
+    let mut map = BitMap::new(size).with_offset(offset);
+    for (i, b) in bits.iter().enumerate() {
+        map.set(
+            offset + Coord::new(i as isize % size.x, i as isize / size.x),
+            *b,
+        );
+    }
+    map
+}
+
+fn set_bits(map: &BitMap) -> BTreeSet<(isize, isize)> {
+    map.iter().map(|c| (c.y, c.x)).collect()
+}
+
+#[test]
+fn map_to_bitmap() {
+    let mut map = Map::new((3, 2).into()).with_offset((-1, 4).into());
+    map.fill(Tile(true));
+    map[Coord::new(0, 5)] = Tile(false);
+
+    let walkable = map.walkable_bitmap();
+    assert_eq!(walkable.area, map.area);
+    assert_eq!(walkable.count_ones(), 5);
+    assert!(!walkable.get((0, 5).into()));
+    assert!(walkable.get((-1, 4).into()));
+    assert!(!walkable.get((5, 5).into()));
+    assert_eq!(walkable, map.transparent_bitmap());
+    assert_eq!(
+        walkable.iter().collect::<Vec<_>>(),
+        vec![
+            Coord::new(-1, 4),
+            Coord::new(0, 4),
+            Coord::new(1, 4),
+            Coord::new(-1, 5),
+            Coord::new(1, 5)
+        ]
+    );
+}
+
+#[test]
+fn fill_keeps_the_count_within_the_area() {
+    let mut map = BitMap::new((9, 9).into());
+    map.fill(true);
+    assert_eq!(map.count_ones(), 81);
+    assert_eq!(map.iter().count(), 81);
+    map.fill(false);
+    assert!(map.is_clear());
+}
+
+proptest! {
+    #[test]
+    fn set_operations_match_sets(
+        a in proptest::collection::vec(any::<bool>(), 130),
+        b in proptest::collection::vec(any::<bool>(), 130),
+        offset in (-5isize..5, -5isize..5),
+    ) {
+        let size = Coord::new(13, 10);
+        let (a, b) = (bitmap(size, offset.into(), &a), bitmap(size, offset.into(), &b));
+        let (sa, sb) = (set_bits(&a), set_bits(&b));
+
+        prop_assert_eq!(a.count_ones(), sa.len());
+        prop_assert_eq!(set_bits(&(&a | &b)), sa.union(&sb).copied().collect::<BTreeSet<_>>());
+        prop_assert_eq!(set_bits(&(&a & &b)), sa.intersection(&sb).copied().collect::<BTreeSet<_>>());
+        prop_assert_eq!(set_bits(&(&a - &b)), sa.difference(&sb).copied().collect::<BTreeSet<_>>());
+        for (y, x) in sa {
+            prop_assert!(a.get((x, y).into()));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use proptest::prelude::*;
+use rl_utils::{ca_generate, Area, CATile, CAparams, Coord, Map, CA};
+
+fn cells(size: Coord, walls: &[bool]) -> Map<CA> {
+    let mut map = Map::new(size);
+    map.fill(CA {
+        tile: CATile::Alive,
+        next: CATile::Alive,
+    });
+    for (i, wall) in walls.iter().enumerate() {
+        let tile = if *wall { CATile::Dead } else { CATile::Alive };
+        map[(i % size.x as usize, i / size.x as usize)] = CA { tile, next: tile };
+    }
+    map
+}
+
+/// One generation calculated cell by cell with [CA::check].
+fn reference(size: Coord, params: &CAparams, cmap: &mut Map<CA>) {
+    let area = Area::new((0, 0).into(), size);
+    let checked: Vec<_> = area
+        .iter()
+        .map(|p| (p, cmap[p].check(p, params, cmap)))
+        .collect();
+    for (p, ca) in checked {
+        cmap[p] = ca.update();
+    }
+}
+
+/// The number of walls [CA::check] counts around `pos` within radius 1 and 2, found by moving
+/// the thresholds of the rule until the outcome flips.
+fn window_counts(pos: Coord, cmap: &Map<CA>) -> (usize, usize) {
+    let dead = |r1, r2| {
+        cmap[pos]
+            .check(pos, &CAparams { count: 1, r1, r2 }, cmap)
+            .next
+            == CATile::Dead
+    };
+    let r2 = (0..=25).find(|n| dead(usize::MAX, *n)).unwrap();
+    // The radius 1 window lies within the radius 2 window.
+    if r2 == 0 {
+        return (0, 0);
+    }
+    let r1 = (0..=9).rev().find(|n| dead(*n, 0)).unwrap();
+    (r1, r2)
+}
+
+#[test]
+fn window_sizes() {
+    let mut all = cells((10, 8).into(), &[true; 80]);
+    // Windows of 3x3 and 5x5 around the cell, which start at the map border when they would
+    // start outside of it.
+    assert_eq!(window_counts((5, 4).into(), &all), (9, 25));
+    assert_eq!(window_counts((0, 0).into(), &all), (9, 25));
+    assert_eq!(window_counts((1, 1).into(), &all), (9, 25));
+    assert_eq!(window_counts((9, 7).into(), &all), (4, 9));
+
+    // A single wall is counted by all windows which contain it.
+    all.fill(CA {
+        tile: CATile::Alive,
+        next: CATile::Alive,
+    });
+    all[Coord::new(5, 4)] = CA {
+        tile: CATile::Dead,
+        next: CATile::Dead,
+    };
+    assert_eq!(window_counts((4, 3).into(), &all), (1, 1));
+    assert_eq!(window_counts((7, 6).into(), &all), (0, 1));
+    assert_eq!(window_counts((8, 4).into(), &all), (0, 0));
+}
+
+proptest! {
+    #[test]
+    fn matches_reference(
+        walls in prop::collection::vec(any::<bool>(), 24 * 20),
+        size in (1isize..24, 1isize..20),
+        r1 in 0usize..10,
+        r2 in 0usize..17,
+        count in 1usize..4,
+    ) {
+        let map_size = Coord::new(24, 20);
+        let params = CAparams { count, r1, r2 };
+        let mut expected = cells(map_size, &walls);
+        let mut actual = expected.clone();
+        for _ in 0..count {
+            reference(size.into(), &params, &mut expected);
+            ca_generate(size.into(), &params, &mut actual);
+        }
+        prop_assert_eq!(actual, expected);
+    }
+}
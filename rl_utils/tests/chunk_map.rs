@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+mod common;
+
+use common::Tile;
+use rl_utils::{Area, ChunkMap, Coord, Map, MapMovement, PathConfig};
+
+/// Every tile with x divisible by 5 is a wall, except where y is divisible by 4.
+fn world(pos: Coord) -> Tile {
+    Tile(pos.x.rem_euclid(5) != 0 || pos.y.rem_euclid(4) == 0)
+}
+
+fn generator(generated: Rc<RefCell<Vec<Coord>>>) -> impl FnMut(Coord) -> Map<Tile> {
+    move |chunk| {
+        generated.borrow_mut().push(chunk);
+        let mut map = Map::new((4, 3).into());
+        map.fill(Tile(false));
+        for y in 0..3 {
+            for x in 0..4 {
+                map[(x, y)] = world(Coord::new(chunk.x * 4 + x, chunk.y * 3 + y));
+            }
+        }
+        map
+    }
+}
+
+#[test]
+fn chunks_are_generated_lazily() {
+    let generated = Rc::new(RefCell::new(vec![]));
+    let mut chunks = ChunkMap::new((4, 3).into(), generator(generated.clone()));
+
+    assert_eq!(chunks.get((-1, -1).into()), None);
+    assert_eq!(chunks.chunk_of((-1, -1).into()), Coord::new(-1, -1));
+    assert_eq!(chunks.chunk_of((4, 2).into()), Coord::new(1, 0));
+
+    assert_eq!(*chunks.get_or_load((-1, -1).into()), world((-1, -1).into()));
+    assert_eq!(*chunks.get_or_load((-3, -2).into()), world((-3, -2).into()));
+    assert_eq!(*generated.borrow(), vec![Coord::new(-1, -1)]);
+
+    chunks.load_area(Area::new((-1, -1).into(), (6, 2).into()));
+    assert_eq!(
+        chunks.loaded_chunks(),
+        vec![
+            Coord::new(-1, -1),
+            Coord::new(-1, 0),
+            Coord::new(0, -1),
+            Coord::new(0, 0),
+            Coord::new(1, -1),
+            Coord::new(1, 0)
+        ]
+    );
+    assert_eq!(chunks.iter().count(), 6 * 12);
+    for (pos, tile) in chunks.iter() {
+        assert_eq!(tile, world(pos));
+    }
+}
+
+#[test]
+fn least_recently_used_chunks_go_to_the_sink() {
+    let generated = Rc::new(RefCell::new(vec![]));
+    let unloaded = Rc::new(RefCell::new(vec![]));
+    let sink = unloaded.clone();
+    let mut chunks = ChunkMap::new((4, 3).into(), generator(generated.clone()))
+        .with_max_loaded(2)
+        .with_sink(move |chunk, map: Map<Tile>| sink.borrow_mut().push((chunk, map.area)));
+
+    chunks.load((0, 0).into());
+    chunks.load((1, 0).into());
+    assert!(chunks.get((0, 0).into()).is_some());
+    chunks.load((2, 0).into());
+    assert_eq!(
+        *unloaded.borrow(),
+        vec![(Coord::new(1, 0), Area::new((4, 0).into(), (4, 3).into()))]
+    );
+
+    chunks.load((1, 0).into());
+    assert_eq!(unloaded.borrow()[1].0, Coord::new(0, 0));
+    assert_eq!(generated.borrow().len(), 4);
+
+    chunks.unload_all();
+    assert!(chunks.loaded_chunks().is_empty());
+    assert_eq!(unloaded.borrow().len(), 4);
+}
+
+#[test]
+fn path_finding_across_chunk_borders() {
+    let generated = Rc::new(RefCell::new(vec![]));
+    let mut chunks = ChunkMap::new((4, 3).into(), generator(generated)).with_max_loaded(1);
+
+    let area = Area::new((-7, -5).into(), (17, 11).into());
+    let window = chunks.to_map(area);
+    let mut expected = Map::new(area.size).with_offset(area.position);
+    expected.fill(Tile(false));
+    for y in 0..area.size.y {
+        for x in 0..area.size.x {
+            let p = expected.to_world((x, y).into());
+            expected[p] = world(p);
+        }
+    }
+    assert_eq!(window.area, area);
+    assert_eq!(window.data, expected.data[..(17 * 11)].to_vec());
+
+    let (start, goal) = (Coord::new(-6, -3), Coord::new(9, 5));
+    let path = window.astar(start, goal, &PathConfig::new()).unwrap();
+    assert_eq!(path.goal(), goal);
+
+    let generated = Rc::new(RefCell::new(vec![]));
+    let mut chunks = ChunkMap::new((4, 3).into(), generator(generated));
+    assert_eq!(chunks.astar(start, goal, &PathConfig::new()), None);
+    chunks.load_area(area);
+    for pair in path.as_slice().windows(2) {
+        assert!(chunks
+            .walkable_tiles(pair[0], MapMovement::Both)
+            .iter()
+            .any(|(n, _)| *n == pair[1]));
+    }
+
+    // Searching the loaded chunks directly costs as much as searching the copy.
+    let direct = chunks.astar(start, goal, &PathConfig::new()).unwrap();
+    assert_eq!(direct.cost(), path.cost());
+    assert_eq!(direct.start(), start);
+    assert_eq!(direct.goal(), goal);
+}
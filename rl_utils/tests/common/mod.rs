@@ -0,0 +1,64 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use rl_utils::{Coord, Map, MapObject, MovementCost};
+
+/// A tile which is either walkable and transparent, or a wall.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile(pub bool);
+impl MapObject for Tile {
+    fn is_transparent(&self) -> bool {
+        self.0
+    }
+    fn is_walkable(&self) -> MovementCost {
+        if self.0 {
+            MovementCost::Possible(1)
+        } else {
+            MovementCost::Impossible
+        }
+    }
+}
+
+/// A tile which costs its value to enter, a value of 0 is a wall.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostTile(pub u8);
+impl MapObject for CostTile {
+    fn is_transparent(&self) -> bool {
+        self.0 != 0
+    }
+    fn is_walkable(&self) -> MovementCost {
+        match self.0 {
+            0 => MovementCost::Impossible,
+            cost => MovementCost::Possible(cost as usize),
+        }
+    }
+}
+
+/// A map drawn as rows of text, '.' is walkable and everything else a wall.
+pub fn from_rows(rows: &[&str]) -> Map<Tile> {
+    let mut map = Map::new((rows[0].len(), rows.len()).into());
+    map.fill(Tile(false));
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            map[(x, y)] = Tile(c == '.');
+        }
+    }
+    map
+}
+
+/// A map of `size` filled row by row from `walkable`, the tiles past its end are walls.
+pub fn tiles(size: Coord, walkable: &[bool]) -> Map<Tile> {
+    let mut map = Map::new(size);
+    map.fill(Tile(false));
+    for (i, w) in walkable.iter().enumerate() {
+        map[(i % size.x as usize, i / size.x as usize)] = Tile(*w);
+    }
+    map
+}
+
+/// A map whose tiles are numbered row by row, starting at 0.
+pub fn numbered(size: Coord, offset: Coord) -> Map<isize> {
+    let mut map = Map::new(size).with_offset(offset);
+    map.data = (0..size.x * size.y).collect();
+    map
+}
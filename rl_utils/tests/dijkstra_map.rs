@@ -0,0 +1,258 @@
+use proptest::prelude::*;
+use rl_utils::{Coord, DijkstraMap, DijkstraMapValue, Map, MapMovement, MapObject, MovementCost};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Terrain {
+    Floor,
+    Swamp,
+    Wall,
+}
+impl MapObject for Terrain {
+    fn is_transparent(&self) -> bool {
+        *self != Terrain::Wall
+    }
+    fn is_walkable(&self) -> MovementCost {
+        match self {
+            Terrain::Floor => MovementCost::Possible(1),
+            Terrain::Swamp => MovementCost::Possible(5),
+            Terrain::Wall => MovementCost::Impossible,
+        }
+    }
+}
+
+fn terrain(rows: &[&str]) -> Map<Terrain> {
+    let size = Coord::new(rows[0].len() as isize, rows.len() as isize);
+    let mut map = Map::new(size);
+    map.fill(Terrain::Floor);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            map[(x, y)] = match c {
+                '~' => Terrain::Swamp,
+                '#' => Terrain::Wall,
+                _ => Terrain::Floor,
+            };
+        }
+    }
+    map
+}
+
+fn value(dmap: &DijkstraMap, x: isize, y: isize) -> isize {
+    dmap.map[(x, y)].to_value()
+}
+
+#[test]
+fn weighted_avoids_expensive_tiles() {
+    let t = terrain(&[".....", ".~~~.", ".~~~.", "....."]);
+    let dmap = DijkstraMap::new(t.area.size)
+        .with_goal((0, 1).into())
+        .with_movement(MapMovement::Orthogonal)
+        .with_movement_costs(&t)
+        .calculate_weighted();
+
+    assert_eq!(value(&dmap, 1, 1), 5);
+    assert_eq!(value(&dmap, 4, 1), 6);
+    assert_eq!(value(&dmap, 2, 1), 8);
+}
+
+#[test]
+fn weighted_honours_walls_and_diagonal_cost() {
+    let t = terrain(&["...", ".#.", "..."]);
+    let dmap = DijkstraMap::new(t.area.size)
+        .with_goal((0, 0).into())
+        .with_diagonal_cost(1.5)
+        .with_movement_costs(&t)
+        .calculate_weighted();
+
+    assert_eq!(dmap.map[Coord::new(1, 1)], DijkstraMapValue::Impassable);
+    assert_eq!(value(&dmap, 1, 0), 1);
+    assert_eq!(value(&dmap, 2, 1), 3);
+    assert_eq!(value(&dmap, 2, 2), 4);
+}
+
+#[test]
+fn weighted_keeps_small_diagonal_multipliers() {
+    let t = terrain(&["....", "....", "...."]);
+    let dmap = DijkstraMap::new(t.area.size)
+        .with_goal((0, 0).into())
+        .with_diagonal_cost(1.41)
+        .with_movement_costs(&t)
+        .calculate_weighted();
+
+    // Rounded once from 1.41, 2.82 and 3.41 rather than after every step.
+    assert_eq!(value(&dmap, 1, 1), 1);
+    assert_eq!(value(&dmap, 2, 2), 3);
+    assert_eq!(value(&dmap, 3, 1), 3);
+    assert_eq!(value(&dmap, 3, 0), 3);
+}
+
+#[test]
+fn weighted_unit_diagonal_cost_keeps_the_units() {
+    let t = terrain(&[".....", ".#.#.", "...#.", "#...."]);
+    let seed = |dmap: DijkstraMap| dmap.with_goal((0, 0).into()).with_movement_costs(&t);
+    let plain = seed(DijkstraMap::new(t.area.size)).calculate_weighted();
+    let unit = seed(DijkstraMap::new(t.area.size))
+        .with_diagonal_cost(1.0)
+        .calculate_weighted();
+
+    assert_eq!(plain.map, unit.map);
+    assert_eq!(plain.within_cost(3), unit.within_cost(3));
+}
+
+#[test]
+fn weighted_matches_calculate_on_uniform_maps() {
+    let t = terrain(&["......", ".#..#.", "......", "..##.."]);
+    let seed = |dmap: DijkstraMap| dmap.with_goal((5, 3).into()).with_movement_costs(&t);
+    let unit = seed(DijkstraMap::new(t.area.size)).calculate();
+    let weighted = seed(DijkstraMap::new(t.area.size)).calculate_weighted();
+
+    assert_eq!(unit.map, weighted.map);
+}
+
+fn row(dmap: &DijkstraMap) -> Vec<isize> {
+    (0..dmap.map.area.size.x)
+        .map(|x| value(dmap, x, 0))
+        .collect()
+}
+
+#[test]
+fn safety_flows_around_threats() {
+    let t = terrain(&["........"]);
+    let threat = DijkstraMap::new(t.area.size)
+        .with_goal((1, 0).into())
+        .with_movement_costs(&t)
+        .calculate_weighted();
+
+    let safety = threat.safety(-1.2);
+    assert_eq!(row(&safety), vec![-1, -1, -2, -3, -4, -5, -6, -7]);
+}
+
+#[test]
+fn safety_from_combines_threats() {
+    let t = terrain(&["........"]);
+    let threat = |x: isize| {
+        DijkstraMap::new(t.area.size)
+            .with_goal((x, 0).into())
+            .with_movement_costs(&t)
+            .calculate_weighted()
+    };
+    let (left, right) = (threat(0), threat(7));
+
+    let safety = DijkstraMap::safety_from(&[&left, &right], -1.2).unwrap();
+    assert_eq!(row(&safety), vec![-1, -2, -3, -4, -4, -3, -2, -1]);
+    assert!(DijkstraMap::safety_from(&[], -1.2).is_none());
+}
+
+#[test]
+fn downhill_path_reaches_goal() {
+    let t = terrain(&[".....", ".###.", "....."]);
+    let dmap = DijkstraMap::new(t.area.size)
+        .with_goal((4, 2).into())
+        .with_movement(MapMovement::Orthogonal)
+        .with_movement_costs(&t)
+        .calculate_weighted();
+
+    assert_eq!(dmap.next_step((0, 0).into()), Some((1, 0).into()));
+    assert_eq!(dmap.next_step((4, 2).into()), None);
+
+    let path = dmap.downhill_path((0, 0).into());
+    assert_eq!(path.len(), 6);
+    assert_eq!(path.last(), Some(&(4, 2).into()));
+    assert!(path.windows(2).all(|w| w[0].delta_abs_total(w[1]) == 1));
+}
+
+#[test]
+fn within_cost_limits_tiles() {
+    let t = terrain(&["....."]);
+    let dmap = DijkstraMap::new(t.area.size)
+        .with_goal((0, 0).into())
+        .with_movement_costs(&t)
+        .calculate_weighted();
+
+    let tiles = dmap.within_cost(2);
+    assert_eq!(tiles, vec![(0, 0).into(), (1, 0).into(), (2, 0).into()]);
+}
+
+#[derive(Debug, Clone)]
+enum Update {
+    AddGoal(Coord),
+    RemoveGoal(Coord),
+    Impassable(Coord),
+    Passable(Coord, usize),
+}
+
+fn update_strategy(size: Coord) -> impl Strategy<Value = Update> {
+    let coord = (0..size.x, 0..size.y).prop_map(|(x, y)| Coord::new(x, y));
+    prop_oneof![
+        coord.clone().prop_map(Update::AddGoal),
+        coord.clone().prop_map(Update::RemoveGoal),
+        coord.clone().prop_map(Update::Impassable),
+        (coord, prop_oneof![Just(1usize), Just(5usize)])
+            .prop_map(|(c, cost)| Update::Passable(c, cost)),
+    ]
+}
+
+fn movement_strategy() -> impl Strategy<Value = MapMovement> {
+    prop_oneof![
+        Just(MapMovement::Orthogonal),
+        Just(MapMovement::Diagonal),
+        Just(MapMovement::Both),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn incremental_updates_match_full_calculation(
+        tiles in proptest::collection::vec(0..4u8, 48),
+        movement in movement_strategy(),
+        diagonal_cost in prop_oneof![Just(1.0f32), Just(1.5f32)],
+        updates in proptest::collection::vec(update_strategy(Coord::new(8, 6)), 1..12),
+    ) {
+        let size = Coord::new(8, 6);
+        let mut t = Map::new(size);
+        t.fill(Terrain::Floor);
+        for (i, tile) in tiles.iter().enumerate() {
+            t[(i % 8, i / 8)] = match tile {
+                0 => Terrain::Wall,
+                1 => Terrain::Swamp,
+                _ => Terrain::Floor,
+            };
+        }
+        let mut goals = vec![Coord::new(0, 0)];
+        let full = |t: &Map<Terrain>, goals: &[Coord]| {
+            goals
+                .iter()
+                .fold(DijkstraMap::new(size), |dmap, g| dmap.with_goal(*g))
+                .with_movement(movement)
+                .with_diagonal_cost(diagonal_cost)
+                .with_movement_costs(t)
+                .calculate_weighted()
+        };
+
+        let mut dmap = full(&t, &goals);
+        for update in updates {
+            match update {
+                Update::AddGoal(c) => {
+                    dmap.add_goal(c);
+                    if !goals.contains(&c) {
+                        goals.push(c);
+                    }
+                }
+                Update::RemoveGoal(c) => {
+                    dmap.remove_goal(c);
+                    goals.retain(|g| *g != c);
+                }
+                Update::Impassable(c) => {
+                    dmap.set_impassable(c);
+                    goals.retain(|g| *g != c);
+                    t[c] = Terrain::Wall;
+                }
+                Update::Passable(c, cost) => {
+                    dmap.set_passable(c, cost);
+                    t[c] = if cost == 1 { Terrain::Floor } else { Terrain::Swamp };
+                }
+            }
+            let expected = full(&t, &goals);
+            prop_assert_eq!(&dmap.map, &expected.map);
+        }
+    }
+}
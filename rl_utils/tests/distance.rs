@@ -0,0 +1,93 @@
+use proptest::prelude::*;
+use rl_utils::{Coord, Distance, Map};
+
+#[test]
+fn metrics() {
+    let (a, b) = (Coord::new(-2, 1), Coord::new(3, 4));
+    assert_eq!(a.distance(b, Distance::Chebyshev), 5.0);
+    assert_eq!(a.distance(b, Distance::Manhattan), 8.0);
+    assert_eq!(a.distance(b, Distance::RoguelikeDiagonal), 6.0);
+    assert!((a.distance(b, Distance::Octile) - (2.0 + 3.0 * 2f64.sqrt())).abs() < 1e-9);
+    assert!((a.distance(b, Distance::Euclidean) - 34f64.sqrt()).abs() < 1e-9);
+
+    assert!(Distance::Euclidean.within((0, 0).into(), (3, 4).into(), 5));
+    assert!(!Distance::Euclidean.within((0, 0).into(), (4, 4).into(), 5));
+    assert!(Distance::RoguelikeDiagonal.within((0, 0).into(), (3, 3).into(), 4));
+    assert!(!Distance::RoguelikeDiagonal.within((0, 0).into(), (4, 4).into(), 5));
+
+    assert_eq!(Distance::Chebyshev.estimate(a, b), 5);
+    assert_eq!(Distance::Manhattan.estimate(a, b), 8);
+    assert_eq!(Distance::Octile.estimate(a, b), 2 * 5 + 3 * 7);
+    assert_eq!(Distance::RoguelikeDiagonal.estimate(a, b), 2 * 2 + 3 * 3);
+}
+
+proptest! {
+    #[test]
+    fn within_matches_measure(
+        metric in prop_oneof![
+            Just(Distance::Chebyshev),
+            Just(Distance::Manhattan),
+            Just(Distance::Euclidean),
+            Just(Distance::Octile),
+            Just(Distance::RoguelikeDiagonal),
+        ],
+        to in (-40isize..40, -40isize..40),
+        radius in 0isize..60,
+    ) {
+        let (from, to) = (Coord::new(0, 0), Coord::from(to));
+        prop_assert_eq!(metric.within(from, to, radius), metric.measure(from, to) <= radius as f64);
+    }
+
+    #[test]
+    fn distance_transform_matches_brute_force(
+        sources in proptest::collection::vec(proptest::bool::weighted(0.1), 117),
+        metric in prop_oneof![
+            Just(Distance::Chebyshev),
+            Just(Distance::Manhattan),
+            Just(Distance::Euclidean),
+            Just(Distance::Octile),
+            Just(Distance::RoguelikeDiagonal),
+        ],
+    ) {
+        let size = Coord::new(13, 9);
+        let mut map = Map::new(size);
+        map.fill(false);
+        for (i, s) in sources.iter().enumerate() {
+            map[(i % 13, i / 13)] = *s;
+        }
+
+        let dist = map.distance_transform(metric, |_, s| *s);
+        let squared = map.squared_distance_transform(|_, s| *s);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let p = Coord::new(x, y);
+                let nearest = |measure: &dyn Fn(Coord) -> u32| {
+                    let mut best = u32::MAX;
+                    for sy in 0..size.y {
+                        for sx in 0..size.x {
+                            let s = Coord::new(sx, sy);
+                            if map[s] {
+                                best = best.min(measure(s));
+                            }
+                        }
+                    }
+                    best
+                };
+                let expected = match metric {
+                    Distance::Chebyshev => nearest(&|s| p.pyth(s) as u32),
+                    Distance::Manhattan => nearest(&|s| p.delta_abs_total(s) as u32),
+                    Distance::Euclidean => {
+                        let sq = nearest(&|s| (p.real_pyth(s).powi(2).round()) as u32);
+                        if sq == u32::MAX { sq } else { (sq as f64).sqrt().round() as u32 }
+                    }
+                    _ => nearest(&|s| metric.measure(p, s).round() as u32),
+                };
+                prop_assert_eq!(dist[p], expected);
+                prop_assert_eq!(
+                    squared[p],
+                    nearest(&|s| (p.real_pyth(s).powi(2).round()) as u32)
+                );
+            }
+        }
+    }
+}
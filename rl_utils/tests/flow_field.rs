@@ -0,0 +1,30 @@
+mod common;
+
+use common::from_rows;
+use rl_utils::{Coord, FlowField, Map, MapMovement};
+
+#[test]
+fn congestion_spreads_units_over_corridors() {
+    let map = from_rows(&["........", ".######.", "........"]);
+    let goal = Coord::new(7, 1);
+    let start = Coord::new(0, 1);
+
+    let field = FlowField::from_map(&map, &[goal], MapMovement::Orthogonal);
+    assert_eq!(field.direction(goal), None);
+    assert_eq!(field.next_step(start), Some((0, 0).into()));
+
+    let mut congestion = Map::new(map.area.size);
+    congestion.fill(0);
+    for x in 1..7usize {
+        congestion[(x, 0usize)] = 3;
+    }
+    let field =
+        FlowField::from_map_with_congestion(&map, &[goal], MapMovement::Orthogonal, &congestion);
+    assert_eq!(field.next_step(start), Some((0, 2).into()));
+
+    let mut pos = start;
+    while let Some(next) = field.next_step(pos) {
+        pos = next;
+    }
+    assert_eq!(pos, goal);
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+use rl_utils::{
+    delaunay, gabriel, lloyd, minimum_spanning_tree, relative_neighbourhood, voronoi, Area, Coord,
+    Distance, Edge,
+};
+
+fn sites() -> impl Strategy<Value = Vec<Coord>> {
+    prop::collection::vec((-30isize..30, -30isize..30), 1..40)
+        .prop_map(|v| v.into_iter().map(Coord::from).collect())
+}
+
+/// Whether `edges` connect all sites.
+fn connected(count: usize, edges: &[Edge]) -> bool {
+    let mut seen = vec![false; count];
+    let mut stack = vec![0];
+    while let Some(i) = stack.pop() {
+        if !seen[i] {
+            seen[i] = true;
+            for (a, b) in edges {
+                if *a == i {
+                    stack.push(*b);
+                } else if *b == i {
+                    stack.push(*a);
+                }
+            }
+        }
+    }
+    seen.iter().all(|s| *s)
+}
+
+#[test]
+fn small_sets() {
+    assert!(delaunay(&[]).is_empty());
+    assert!(delaunay(&[Coord::new(1, 1)]).is_empty());
+
+    let line = [(0, 0).into(), (9, 0).into(), (3, 0).into()];
+    assert_eq!(delaunay(&line), vec![(0, 2), (1, 2)]);
+
+    // A square has 5 Delaunay edges, the relative neighbourhood graph drops the diagonal.
+    let square = [(0, 0).into(), (4, 0).into(), (4, 3).into(), (0, 3).into()];
+    assert_eq!(delaunay(&square).len(), 5);
+    assert_eq!(
+        relative_neighbourhood(&square),
+        vec![(0, 1), (0, 3), (1, 2), (2, 3)]
+    );
+    assert_eq!(minimum_spanning_tree(&square).len(), 3);
+}
+
+#[test]
+fn regions() {
+    let sites = [(2, 2).into(), (17, 2).into(), (10, 12).into()];
+    let area = Area::new((0, 0).into(), (20, 15).into());
+    let regions = voronoi(&sites, area, Distance::Euclidean);
+    for (i, site) in sites.iter().enumerate() {
+        assert_eq!(regions[*site], i);
+    }
+    assert_eq!(regions[Coord::new(0, 14)], 2);
+
+    // Sites clumped in a corner spread over the whole area.
+    let clumped: Vec<Coord> = (0..9).map(|i| Coord::new(i % 3, i / 3)).collect();
+    let relaxed = lloyd(&clumped, area, Distance::Euclidean, 20);
+    assert!(relaxed.iter().all(|s| area.point_within(*s)));
+    assert!(relaxed.iter().any(|s| s.x > 12));
+    assert!(relaxed.iter().any(|s| s.y > 9));
+}
+
+proptest! {
+    #[test]
+    fn graphs(sites in sites()) {
+        let all = delaunay(&sites);
+        let gabriel = gabriel(&sites);
+        let rng = relative_neighbourhood(&sites);
+        let mst = minimum_spanning_tree(&sites);
+
+        prop_assert!(all.iter().all(|(a, b)| a < b && *b < sites.len()));
+        prop_assert!(all.windows(2).all(|w| w[0] < w[1]));
+        prop_assert!(connected(sites.len(), &all));
+        prop_assert!(connected(sites.len(), &mst));
+        prop_assert_eq!(mst.len(), sites.len() - 1);
+
+        // Every graph is contained in the next one.
+        let contains = |outer: &[Edge], inner: &[Edge]| {
+            let outer: HashSet<_> = outer.iter().collect();
+            inner.iter().all(|e| outer.contains(e))
+        };
+        prop_assert!(contains(&all, &gabriel));
+        prop_assert!(contains(&gabriel, &rng));
+        prop_assert!(contains(&rng, &mst) || has_ties(&sites));
+
+        // Every pair with an empty circle between them is a Gabriel edge, duplicate sites are
+        // only connected to their first occurrence.
+        let squared = |a: Coord, b: Coord| (a - b).x.pow(2) + (a - b).y.pow(2);
+        let first = |i: usize| !sites[..i].contains(&sites[i]);
+        for (a, sa) in sites.iter().enumerate().filter(|(a, _)| first(*a)) {
+            for (b, sb) in sites.iter().enumerate().skip(a + 1).filter(|(b, _)| first(*b)) {
+                let length = squared(*sa, *sb);
+                if !sites
+                    .iter()
+                    .any(|c| c != sa && c != sb && squared(*sa, *c) + squared(*sb, *c) <= length)
+                {
+                    prop_assert!(gabriel.contains(&(a, b)), "{:?}", (a, b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_site(sites in sites(), metric in prop_oneof![
+        Just(Distance::Chebyshev),
+        Just(Distance::Manhattan),
+        Just(Distance::Euclidean),
+    ]) {
+        let area = Area::new((-5, -5).into(), (20, 15).into());
+        let regions = voronoi(&sites, area, metric);
+        for (p, region) in regions.iter() {
+            let d = metric.measure(sites[region], p);
+            prop_assert!(sites.iter().all(|s| metric.measure(*s, p) >= d));
+        }
+    }
+}
+
+/// With equally long edges the minimum spanning tree may pick an edge which the relative
+/// neighbourhood graph drops.
+fn has_ties(sites: &[Coord]) -> bool {
+    let mut lengths = HashSet::new();
+    for (i, a) in sites.iter().enumerate() {
+        for b in &sites[i + 1..] {
+            let d = *a - *b;
+            if !lengths.insert(d.x * d.x + d.y * d.y) {
+                return true;
+            }
+        }
+    }
+    false
+}
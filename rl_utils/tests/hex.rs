@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+mod common;
+
+use common::CostTile;
+use proptest::prelude::*;
+use rl_utils::{Coord, DijkstraMapValue, HexCoord, HexMap, OffsetLayout};
+
+fn layout() -> impl Strategy<Value = OffsetLayout> {
+    prop_oneof![
+        Just(OffsetLayout::OddR),
+        Just(OffsetLayout::EvenR),
+        Just(OffsetLayout::OddQ),
+        Just(OffsetLayout::EvenQ),
+    ]
+}
+
+fn hexes(size: Coord, layout: OffsetLayout, costs: &[u8]) -> HexMap<CostTile> {
+    let mut map = HexMap::new(size, layout);
+    map.fill(CostTile(1));
+    for (i, cost) in costs.iter().enumerate() {
+        map.map[(i % size.x as usize, i / size.x as usize)] = CostTile(*cost);
+    }
+    map
+}
+
+#[test]
+fn neighbours() {
+    let center = HexCoord::new(2, -5);
+    for n in center.neighbours().iter() {
+        assert_eq!(center.distance(*n), 1);
+    }
+    assert_eq!(HexCoord::from_cube(1, 2, -3).cube(), (1, 2, -3));
+}
+
+#[test]
+fn rings_and_spirals() {
+    let center = HexCoord::new(-1, 3);
+    assert_eq!(center.ring(0).collect::<Vec<_>>(), vec![center]);
+    for radius in 1..6 {
+        let ring: HashSet<_> = center.ring(radius).collect();
+        assert_eq!(ring.len(), 6 * radius as usize);
+        assert!(ring.iter().all(|h| center.distance(*h) == radius));
+    }
+
+    let count = 1 + 3 * 4 * 5;
+    let spiral: Vec<_> = center.spiral().take(count).collect();
+    assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), count);
+    assert!(spiral.iter().all(|h| center.distance(*h) <= 4));
+}
+
+proptest! {
+    #[test]
+    fn offset_roundtrip(x in -50isize..50, y in -50isize..50, layout in layout()) {
+        let pos = Coord::new(x, y);
+        let hex = HexCoord::from_offset(pos, layout);
+        prop_assert_eq!(hex.to_offset(layout), pos);
+        prop_assert_eq!(HexCoord::from_offset(hex.to_offset(layout), layout), hex);
+    }
+
+    #[test]
+    fn lines(q1 in -20isize..20, r1 in -20isize..20, q2 in -20isize..20, r2 in -20isize..20) {
+        let (start, end) = (HexCoord::new(q1, r1), HexCoord::new(q2, r2));
+        let line: Vec<_> = start.line(end).collect();
+        prop_assert_eq!(line.len() as isize, start.distance(end) + 1);
+        prop_assert_eq!(line[0], start);
+        prop_assert_eq!(line[line.len() - 1], end);
+        for pair in line.windows(2) {
+            prop_assert_eq!(pair[0].distance(pair[1]), 1);
+        }
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra_map(
+        costs in prop::collection::vec(0u8..4, 64),
+        layout in layout(),
+        start in (0isize..8, 0isize..8),
+        goal in (0isize..8, 0isize..8),
+    ) {
+        let map = hexes((8, 8).into(), layout, &costs);
+        let start = HexCoord::from_offset(start.into(), layout);
+        let goal = HexCoord::from_offset(goal.into(), layout);
+        let field = map.dijkstra_map(&[goal]);
+
+        // Both count the tiles entered walking away from the goal.
+        let path = map.astar(goal, start);
+        match field[start] {
+            DijkstraMapValue::Goal => prop_assert_eq!(path.unwrap().cost(), 0),
+            DijkstraMapValue::NonGoal(v) => prop_assert_eq!(path.unwrap().cost() as isize, v),
+            _ => prop_assert!(path.is_none()),
+        }
+
+        if let DijkstraMapValue::NonGoal(_) = field[start] {
+            let mut pos = start;
+            let mut steps = 0;
+            while let Some(next) = field.next_step(pos) {
+                prop_assert_eq!(pos.distance(next), 1);
+                pos = next;
+                steps += 1;
+                prop_assert!(steps <= 64);
+            }
+            prop_assert_eq!(pos, goal);
+        }
+    }
+}
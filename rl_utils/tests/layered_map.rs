@@ -0,0 +1,113 @@
+use rl_utils::{Area, Coord, LayeredMap, MapMovement, MapObject, MovementCost, TileProperties};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Terrain {
+    Floor,
+    Wall,
+}
+impl MapObject for Terrain {
+    fn is_transparent(&self) -> bool {
+        *self == Terrain::Floor
+    }
+    fn is_walkable(&self) -> MovementCost {
+        match self {
+            Terrain::Floor => MovementCost::Possible(1),
+            Terrain::Wall => MovementCost::Impossible,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Door {
+    None,
+    Open,
+    Closed,
+}
+impl MapObject for Door {
+    fn is_transparent(&self) -> bool {
+        *self != Door::Closed
+    }
+    fn is_walkable(&self) -> MovementCost {
+        match self {
+            Door::None => MovementCost::Possible(0),
+            Door::Open => MovementCost::Possible(2),
+            Door::Closed => MovementCost::Impossible,
+        }
+    }
+}
+
+#[test]
+fn layers_share_one_area() {
+    let mut layers = LayeredMap::new((4, 3).into())
+        .with_offset((10, 10).into())
+        .with_layer(Terrain::Floor)
+        .with_layer(Door::None)
+        .with_named_layer("light", 0u8)
+        .with_named_layer("memory", 0u8);
+
+    assert_eq!(layers.layer_count(), 4);
+    layers.named_layer_mut::<u8>("light").unwrap()[(11usize, 12usize)] = 9;
+    assert_eq!(
+        layers.named_layer::<u8>("light").unwrap()[(11usize, 12usize)],
+        9
+    );
+    assert_eq!(
+        layers.named_layer::<u8>("memory").unwrap()[(11usize, 12usize)],
+        0
+    );
+    assert!(layers.layer::<u8>().is_none());
+    assert!(layers.named_layer::<u16>("light").is_none());
+
+    layers.set_offset((0, 0).into());
+    assert_eq!(layers.area(), Area::new((0, 0).into(), (4, 3).into()));
+    assert_eq!(
+        layers.named_layer::<u8>("light").unwrap()[(1usize, 2usize)],
+        9
+    );
+    assert_eq!(layers.layer::<Door>().unwrap().area, layers.area());
+
+    layers.resize(Area::new((1, 1).into(), (5, 5).into()));
+    let light = layers.named_layer::<u8>("light").unwrap();
+    assert_eq!(light.area, layers.area());
+    assert_eq!(light[(1usize, 2usize)], 9);
+    assert_eq!(light[(5usize, 5usize)], 0);
+    assert_eq!(
+        layers.layer::<Terrain>().unwrap()[(5usize, 5usize)],
+        Terrain::Floor
+    );
+
+    let removed = layers.remove_named_layer::<u8>("memory").unwrap();
+    assert_eq!(removed.area, layers.area());
+    assert_eq!(layers.layer_count(), 3);
+}
+
+#[test]
+fn combined_layers_block_movement_and_vision() {
+    let mut layers = LayeredMap::new((5, 1).into())
+        .with_layer(Terrain::Floor)
+        .with_layer(Door::None);
+    layers.layer_mut::<Terrain>().unwrap()[(4usize, 0usize)] = Terrain::Wall;
+    layers.layer_mut::<Door>().unwrap()[(1usize, 0usize)] = Door::Open;
+    layers.layer_mut::<Door>().unwrap()[(2usize, 0usize)] = Door::Closed;
+
+    let combined = layers.combine_layers::<Terrain, Door>().unwrap();
+    let tiles: Vec<TileProperties> = combined.iter().map(|(_, t)| t).collect();
+    assert_eq!(
+        tiles.iter().map(|t| t.transparent).collect::<Vec<_>>(),
+        vec![true, true, false, true, false]
+    );
+    assert_eq!(
+        tiles.iter().map(|t| t.walkable).collect::<Vec<_>>(),
+        vec![
+            MovementCost::Possible(1),
+            MovementCost::Possible(2),
+            MovementCost::Impossible,
+            MovementCost::Possible(1),
+            MovementCost::Impossible,
+        ]
+    );
+    assert_eq!(
+        combined.walkable_tiles(Coord::new(1, 0), MapMovement::Orthogonal),
+        vec![(Coord::new(0, 0), 1)]
+    );
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use common::{numbered, tiles};
+use proptest::prelude::*;
+use rl_utils::{Area, Coord, Distance, FlowField, Map, MapMovement, PathConfig};
+
+#[test]
+fn world_and_local_positions() {
+    let mut map = Map::new((3, 2).into()).with_offset((10, 20).into());
+    map.fill(0);
+    map[Coord::new(11, 21)] = 5;
+
+    assert_eq!(map.to_local((11, 21).into()), Coord::new(1, 1));
+    assert_eq!(map.to_world((1, 1).into()), Coord::new(11, 21));
+    assert_eq!(map[(11usize, 21usize)], 5);
+    assert_eq!(map.get((11, 21).into()), Some(&5));
+    assert_eq!(map.get_local((1, 1).into()), Some(&5));
+    assert_eq!(map.get((1, 1).into()), None);
+    assert_eq!(map.get((13, 20).into()), None);
+    assert_eq!(map.get_local((3, 0).into()), None);
+    assert_eq!(map.get_local((-1, 0).into()), None);
+
+    *map.get_mut((10, 20).into()).unwrap() = 7;
+    assert_eq!(map.get_local((0, 0).into()), Some(&7));
+}
+
+#[test]
+#[should_panic]
+fn index_outside_of_offset_map() {
+    let mut map = Map::new((3, 2).into()).with_offset((10, 20).into());
+    map.fill(0);
+    let _ = map[(0usize, 0usize)];
+}
+
+#[test]
+fn import_from_offset_iterator() {
+    let size = Coord::new(4, 3);
+    let offset = Coord::new(-2, 5);
+    let source = tiles(size, &[true, false, true, true, false, true]).with_offset(offset);
+
+    let mut target = tiles(size, &[true; 12]).with_offset(offset);
+    target.import_from_iter(source.iter());
+    for (pos, tile) in source.iter() {
+        assert_eq!(target[pos], tile);
+    }
+}
+
+#[test]
+fn borrowing_iterators() {
+    let mut map = numbered((4, 3).into(), (-1, 2).into());
+
+    let tiles: Vec<(Coord, isize)> = map.iter_ref().map(|(c, t)| (c, *t)).collect();
+    assert_eq!(tiles, map.iter().collect::<Vec<_>>());
+    assert_eq!(tiles[5], (Coord::new(0, 3), 5));
+
+    for (c, t) in map.iter_mut() {
+        *t += c.x * 100;
+    }
+    assert_eq!(map[Coord::new(2, 4)], 200 + 11);
+    for (c, t) in map.iter_mut() {
+        *t -= c.x * 100;
+    }
+
+    let rows: Vec<&[isize]> = map.rows().collect();
+    assert_eq!(
+        rows,
+        vec![&[0, 1, 2, 3][..], &[4, 5, 6, 7], &[8, 9, 10, 11]]
+    );
+    map.rows_mut().nth(1).unwrap().reverse();
+    assert_eq!(map.rows().nth(1).unwrap(), &[7, 6, 5, 4]);
+    map.rows_mut().nth(1).unwrap().reverse();
+
+    // Only the filled in tiles of a map which is not filled yet.
+    let mut unfilled: Map<isize> = Map::new((4, 3).into());
+    assert_eq!(unfilled.rows().count(), 0);
+    assert_eq!(unfilled.rows_mut().count(), 0);
+    unfilled.data = (0..6).collect();
+    assert_eq!(
+        unfilled.rows().collect::<Vec<_>>(),
+        vec![&[0, 1, 2, 3][..], &[4, 5]]
+    );
+
+    let window: Vec<(Coord, isize)> = map
+        .window(Area::new((1, 1).into(), (5, 3).into()))
+        .map(|(c, t)| (c, *t))
+        .collect();
+    assert_eq!(
+        window,
+        vec![
+            (Coord::new(1, 2), 2),
+            (Coord::new(2, 2), 3),
+            (Coord::new(1, 3), 6),
+            (Coord::new(2, 3), 7),
+        ]
+    );
+    for (_, t) in map.window_mut(Area::new((0, 4).into(), (1, 1).into())) {
+        *t = -1;
+    }
+    assert_eq!(map[Coord::new(0, 4)], -1);
+    assert_eq!(
+        map.window(Area::new((9, 9).into(), (2, 2).into())).count(),
+        0
+    );
+
+    let neighbours: Vec<(Coord, isize)> = map
+        .neighbours((-1, 2).into(), MapMovement::Both)
+        .map(|(c, t)| (c, *t))
+        .collect();
+    assert_eq!(
+        neighbours,
+        vec![
+            (Coord::new(0, 2), 1),
+            (Coord::new(-1, 3), 4),
+            (Coord::new(0, 3), 5)
+        ]
+    );
+}
+
+#[test]
+fn area_iter_uses_the_map_stride() {
+    let map = numbered((5, 4).into(), (10, 10).into());
+
+    let tiles: Vec<(Coord, isize)> = map
+        .area_iter(Area::new((11, 12).into(), (3, 2).into()))
+        .unwrap()
+        .collect();
+    assert_eq!(
+        tiles,
+        vec![
+            (Coord::new(11, 12), 11),
+            (Coord::new(12, 12), 12),
+            (Coord::new(13, 12), 13),
+            (Coord::new(11, 13), 16),
+            (Coord::new(12, 13), 17),
+            (Coord::new(13, 13), 18),
+        ]
+    );
+    assert!(map
+        .area_iter(Area::new((9, 12).into(), (3, 2).into()))
+        .is_none());
+}
+
+proptest! {
+    #[test]
+    fn offset_maps_match_maps_at_origin(
+        walkable in proptest::collection::vec(proptest::bool::weighted(0.7), 48),
+        offset in (-20isize..20, -20isize..20),
+        start in (0isize..8, 0isize..6),
+        goal in (0isize..8, 0isize..6),
+    ) {
+        let size = Coord::new(8, 6);
+        let offset: Coord = offset.into();
+        let (start, goal): (Coord, Coord) = (start.into(), goal.into());
+        let origin = tiles(size, &walkable);
+        let shifted = tiles(size, &walkable).with_offset(offset);
+
+        let regions = origin.regions(MapMovement::Both);
+        let shifted_regions = shifted.regions(MapMovement::Both);
+        prop_assert_eq!(&regions.labels.data, &shifted_regions.labels.data);
+
+        let dist = origin.distance_transform(Distance::Manhattan, |_, t| !t.0);
+        let shifted_dist = shifted.distance_transform(Distance::Manhattan, |_, t| !t.0);
+        prop_assert_eq!(shifted_dist.area.position, offset);
+        prop_assert_eq!(&dist.data, &shifted_dist.data);
+
+        let config = PathConfig::new();
+        let path = origin.astar(start, goal, &config);
+        let shifted_path = shifted.astar(start + offset, goal + offset, &config);
+        prop_assert_eq!(
+            path.map(|p| p.iter().map(|c| *c + offset).collect::<Vec<_>>()),
+            shifted_path.map(Vec::from)
+        );
+
+        let flow = FlowField::from_map(&origin, &[goal], MapMovement::Both);
+        let shifted_flow = FlowField::from_map(&shifted, &[goal + offset], MapMovement::Both);
+        prop_assert_eq!(&flow.map.data, &shifted_flow.map.data);
+    }
+}
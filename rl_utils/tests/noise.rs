@@ -0,0 +1,73 @@
+use proptest::prelude::*;
+use rl_utils::{Area, Fractal, Noise, OpenSimplex, Perlin, Value, Warp, Worley, WorleyValue};
+
+fn sources(seed: u64) -> Vec<Box<dyn Noise>> {
+    vec![
+        Box::new(Perlin::new(seed)),
+        Box::new(OpenSimplex::new(seed)),
+        Box::new(Value::new(seed)),
+        Box::new(Fractal::fbm(Perlin::new(seed))),
+        Box::new(Fractal::ridged(OpenSimplex::new(seed)).with_octaves(6)),
+        Box::new(Fractal::turbulence(Value::new(seed)).with_gain(0.6)),
+        Box::new(Warp::new(Perlin::new(seed), Value::new(seed + 1), 4.0)),
+    ]
+}
+
+#[test]
+fn seeds() {
+    for (a, b) in sources(1).iter().zip(sources(1).iter()) {
+        assert_eq!(a.get(3.7, -12.1), b.get(3.7, -12.1));
+    }
+    for (a, b) in sources(1).iter().zip(sources(2).iter()) {
+        assert_ne!(a.get(3.7, -12.1), b.get(3.7, -12.1));
+    }
+}
+
+#[test]
+fn perlin_lattice() {
+    let perlin = Perlin::new(7);
+    for y in -5..5 {
+        for x in -5..5 {
+            assert_eq!(perlin.get(x as f64, y as f64), 0.0);
+        }
+    }
+}
+
+#[test]
+fn chunks_line_up() {
+    let noise = Fractal::fbm(OpenSimplex::new(3));
+    let whole = noise.fill(Area::new((-8, -4).into(), (16, 8).into()), 0.1);
+    let left = noise.fill(Area::new((-8, -4).into(), (8, 8).into()), 0.1);
+    let right = noise.fill(Area::new((0, -4).into(), (8, 8).into()), 0.1);
+    for p in whole.area.iter() {
+        let part = if p.x < 0 { &left } else { &right };
+        assert_eq!(whole[p], part[p]);
+    }
+}
+
+proptest! {
+    #[test]
+    fn ranges(seed in any::<u64>(), x in -1000.0f64..1000.0, y in -1000.0f64..1000.0) {
+        for noise in sources(seed) {
+            let n = noise.get(x, y);
+            prop_assert!((-1.0..=1.0).contains(&n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn continuous(seed in any::<u64>(), x in -1000.0f64..1000.0, y in -1000.0f64..1000.0) {
+        for noise in sources(seed).iter().take(3) {
+            prop_assert!((noise.get(x, y) - noise.get(x + 1e-4, y - 1e-4)).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn worley(seed in any::<u64>(), x in -1000.0f64..1000.0, y in -1000.0f64..1000.0) {
+        let nearest = Worley::new(seed).get(x, y);
+        let second = Worley::new(seed).with_value(WorleyValue::SecondNearest).get(x, y);
+        let difference = Worley::new(seed).with_value(WorleyValue::Difference).get(x, y);
+        prop_assert!((0.0..=second).contains(&nearest));
+        prop_assert!(nearest < 1.5);
+        prop_assert!((difference - (second - nearest)).abs() < 1e-12);
+    }
+}
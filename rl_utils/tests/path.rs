@@ -0,0 +1,184 @@
+mod common;
+
+use common::CostTile;
+use proptest::prelude::*;
+use rl_utils::{
+    Coord, DijkstraMap, DijkstraMapValue, Distance, HierarchicalMap, Map, MapMovement, PathConfig,
+};
+
+fn tiles(size: Coord, costs: &[u8]) -> Map<CostTile> {
+    let mut map = Map::new(size);
+    map.fill(CostTile(1));
+    for (i, cost) in costs.iter().enumerate() {
+        map[(i % size.x as usize, i / size.x as usize)] = CostTile(*cost);
+    }
+    map
+}
+
+fn distance() -> impl Strategy<Value = Distance> {
+    prop_oneof![
+        Just(Distance::Chebyshev),
+        Just(Distance::Manhattan),
+        Just(Distance::Euclidean),
+        Just(Distance::Octile),
+        Just(Distance::RoguelikeDiagonal),
+    ]
+}
+
+/// The cost of entering `to` from `from`, weighed like the path finders do.
+fn step_cost(map: &Map<CostTile>, distance: Distance, from: Coord, to: Coord) -> usize {
+    let (orthogonal, diagonal) = distance.step_weights();
+    let weight = if from.x != to.x && from.y != to.y {
+        diagonal
+    } else {
+        orthogonal
+    };
+    map[to].0 as usize * weight
+}
+
+#[test]
+fn astar_walks_around_walls() {
+    let map = tiles(
+        (5, 3).into(),
+        &[1, 0, 1, 1, 1, 1, 0, 1, 0, 1, 1, 1, 1, 0, 1],
+    );
+    let config = PathConfig::new().with_movement(MapMovement::Orthogonal);
+
+    let path = map.astar((0, 0).into(), (4, 0).into(), &config).unwrap();
+    assert_eq!(path.start(), (0, 0).into());
+    assert_eq!(path.goal(), (4, 0).into());
+    assert_eq!(path.cost(), 8);
+    assert_eq!(path.next_step((0, 0).into()), Some((0, 1).into()));
+
+    assert!(map
+        .astar((0, 0).into(), (4, 0).into(), &config.with_max_cost(7))
+        .is_none());
+    assert!(map.astar((0, 0).into(), (1, 0).into(), &config).is_none());
+}
+
+proptest! {
+    #[test]
+    fn searches_agree_with_dijkstra_map(
+        costs in proptest::collection::vec(prop_oneof![Just(0u8), 1..4u8], 80),
+        movement in prop_oneof![
+            Just(MapMovement::Orthogonal),
+            Just(MapMovement::Diagonal),
+            Just(MapMovement::Both),
+        ],
+        distance in distance(),
+        start in (0..10isize, 0..8isize),
+        goal in (0..10isize, 0..8isize),
+    ) {
+        let map = tiles((10, 8).into(), &costs);
+        let (start, goal): (Coord, Coord) = (start.into(), goal.into());
+        let config = PathConfig::new().with_movement(movement).with_distance(distance);
+
+        let dmap = DijkstraMap::new(map.area.size)
+            .with_goal(start)
+            .with_movement(movement)
+            .with_distance(distance)
+            .with_movement_costs(&map)
+            .calculate_weighted();
+        let expected = match dmap.map[goal] {
+            DijkstraMapValue::Goal => Some(0),
+            DijkstraMapValue::NonGoal(cost) => Some(cost as usize),
+            _ => None,
+        };
+
+        let astar = map.astar(start, goal, &config);
+        let bidirectional = map.bidirectional_astar(start, goal, &config);
+        prop_assert_eq!(astar.as_ref().map(|p| p.cost()), expected);
+        prop_assert_eq!(bidirectional.as_ref().map(|p| p.cost()), expected);
+
+        for path in astar.iter().chain(bidirectional.iter()) {
+            prop_assert_eq!(path.start(), start);
+            prop_assert_eq!(path.goal(), goal);
+            let mut cost = 0;
+            for step in path.as_slice().windows(2) {
+                prop_assert!(movement.get_reachable_tiles().contains(&(step[1] - step[0])));
+                cost += step_cost(&map, distance, step[0], step[1]);
+            }
+            prop_assert_eq!(cost, path.cost());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn jps_agrees_with_astar(
+        costs in proptest::collection::vec(prop_oneof![1 => Just(0u8), 3 => Just(2u8)], 192),
+        varying in proptest::option::of(0..192usize),
+        distance in distance(),
+        start in (0..16isize, 0..12isize),
+        goal in (0..16isize, 0..12isize),
+    ) {
+        let mut costs = costs;
+        if let Some(i) = varying {
+            costs[i] = 3;
+        }
+        let map = tiles((16, 12).into(), &costs);
+        let (start, goal): (Coord, Coord) = (start.into(), goal.into());
+        let config = PathConfig::new().with_distance(distance);
+
+        let astar = map.astar(start, goal, &config);
+        let jps = map.jps(start, goal, &config);
+        prop_assert_eq!(astar.as_ref().map(|p| p.cost()), jps.as_ref().map(|p| p.cost()));
+        if let Some(uniform) = map.uniform_cost() {
+            prop_assert_eq!(&jps, &map.jps_uniform(start, goal, uniform, &config));
+        }
+
+        if let Some(path) = jps {
+            prop_assert_eq!(path.start(), start);
+            prop_assert_eq!(path.goal(), goal);
+            let mut cost = 0;
+            for step in path.as_slice().windows(2) {
+                prop_assert!(step[0].is_neightbour(step[1]) && step[0] != step[1]);
+                cost += step_cost(&map, distance, step[0], step[1]);
+            }
+            prop_assert_eq!(cost, path.cost());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn hierarchical_paths_are_valid(
+        costs in proptest::collection::vec(prop_oneof![1 => Just(0u8), 3 => 1..3u8], 300),
+        movement in prop_oneof![
+            Just(MapMovement::Orthogonal),
+            Just(MapMovement::Diagonal),
+            Just(MapMovement::Both),
+        ],
+        distance in distance(),
+        updates in proptest::collection::vec(((0..20isize, 0..15isize), 0..3u8), 0..6),
+        start in (0..20isize, 0..15isize),
+        goal in (0..20isize, 0..15isize),
+    ) {
+        let mut map = tiles((20, 15).into(), &costs);
+        let (start, goal): (Coord, Coord) = (start.into(), goal.into());
+        let config = PathConfig::new().with_movement(movement).with_distance(distance);
+
+        let mut hmap = HierarchicalMap::new(&map, (6, 5).into(), config);
+        for (pos, cost) in updates {
+            map[pos] = CostTile(cost);
+            hmap.update_tile(&map, pos.into());
+        }
+        prop_assert_eq!(&hmap, &HierarchicalMap::new(&map, (6, 5).into(), config));
+
+        let astar = map.astar(start, goal, &config);
+        let path = hmap.find_path(&map, start, goal);
+        prop_assert_eq!(astar.is_some(), path.is_some());
+
+        if let (Some(astar), Some(path)) = (astar, path) {
+            prop_assert!(path.cost() >= astar.cost());
+            prop_assert_eq!(path.start(), start);
+            prop_assert_eq!(path.goal(), goal);
+            let mut cost = 0;
+            for step in path.as_slice().windows(2) {
+                prop_assert!(movement.get_reachable_tiles().contains(&(step[1] - step[0])));
+                cost += step_cost(&map, distance, step[0], step[1]);
+            }
+            prop_assert_eq!(cost, path.cost());
+        }
+    }
+}
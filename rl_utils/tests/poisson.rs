@@ -0,0 +1,65 @@
+use proptest::prelude::*;
+use rl_utils::{Area, Coord, Map, PoissonDisk};
+
+fn squared(a: Coord, b: Coord) -> f64 {
+    let d = a - b;
+    (d.x * d.x + d.y * d.y) as f64
+}
+
+#[test]
+fn seeds() {
+    let area = Area::new((-10, 5).into(), (40, 30).into());
+    let a = PoissonDisk::new(area, 3.0, 1).sample();
+    assert_eq!(a, PoissonDisk::new(area, 3.0, 1).sample());
+    assert_ne!(a, PoissonDisk::new(area, 3.0, 2).sample());
+}
+
+#[test]
+fn disconnected_regions() {
+    // Two rooms far apart, separated by walls.
+    let mut map = Map::new((40, 10).into());
+    map.fill_each(|p| p.x < 8 || p.x >= 32);
+    let samples = PoissonDisk::new(map.area, 2.5, 9).sample_map(&map, |open| *open);
+    assert!(samples.iter().all(|p| map[*p]));
+    assert!(samples.iter().any(|p| p.x < 8));
+    assert!(samples.iter().any(|p| p.x >= 32));
+}
+
+proptest! {
+    #[test]
+    fn spaced_and_maximal(
+        seed in any::<u64>(),
+        radius in 1.0f64..6.0,
+        position in (-20isize..20, -20isize..20),
+        size in (1isize..40, 1isize..40),
+    ) {
+        let area = Area::new(position.into(), size.into());
+        let samples = PoissonDisk::new(area, radius, seed).sample();
+        prop_assert!(!samples.is_empty());
+        for (i, a) in samples.iter().enumerate() {
+            prop_assert!(area.point_within(*a));
+            for b in &samples[i + 1..] {
+                prop_assert!(squared(*a, *b) >= radius * radius);
+            }
+        }
+        for p in area.iter() {
+            prop_assert!(samples.iter().any(|s| squared(*s, p) < radius * radius));
+        }
+    }
+
+    #[test]
+    fn variable_radius(seed in any::<u64>()) {
+        // Samples get sparser towards the right.
+        let area = Area::new((0, 0).into(), (48, 24).into());
+        let radius = |p: Coord| 2.0 + p.x as f64 / 8.0;
+        let samples = PoissonDisk::new(area, 2.0, seed).sample_by(|_| true, radius);
+        for (i, a) in samples.iter().enumerate() {
+            for b in &samples[i + 1..] {
+                let min = radius(*a).max(radius(*b));
+                prop_assert!(squared(*a, *b) >= min * min);
+            }
+        }
+        let left = samples.iter().filter(|p| p.x < 24).count();
+        prop_assert!(left > samples.len() - left);
+    }
+}
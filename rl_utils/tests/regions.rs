@@ -0,0 +1,88 @@
+mod common;
+
+use common::{tiles, Tile};
+use proptest::prelude::*;
+use rl_utils::{Coord, MapMovement};
+
+#[test]
+fn regions_and_adjacency() {
+    let map = tiles(
+        (5, 3).into(),
+        &[
+            true, true, false, true, true, //
+            true, true, false, true, true, //
+            false, false, false, false, true,
+        ],
+    );
+    let regions = map.regions(MapMovement::Orthogonal);
+
+    assert_eq!(regions.count(), 2);
+    assert_eq!(regions.sizes(), &[4, 5]);
+    assert_eq!(regions.largest(), Some(1));
+    assert_eq!(regions.region((0, 0).into()), Some(0));
+    assert_eq!(regions.region((2, 0).into()), None);
+    assert_eq!(
+        regions.adjacency().get(&(0, 1)),
+        Some(&vec![(2, 0).into(), (2, 1).into()])
+    );
+}
+
+proptest! {
+    #[test]
+    fn chokepoints_match_brute_force(
+        walkable in proptest::collection::vec(proptest::bool::weighted(0.7), 48),
+        movement in prop_oneof![Just(MapMovement::Orthogonal), Just(MapMovement::Both)],
+    ) {
+        let size = Coord::new(8, 6);
+        let map = tiles(size, &walkable);
+        let count = map.regions(movement).count();
+
+        let mut articulation = vec![];
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let p = Coord::new(x, y);
+                if !map[p].0 {
+                    continue;
+                }
+                let mut blocked = map.clone();
+                blocked[p] = Tile(false);
+                if blocked.regions(movement).count() > count {
+                    articulation.push(p);
+                }
+            }
+        }
+        prop_assert_eq!(map.articulation_tiles(movement), articulation);
+
+        let mut bridges = vec![];
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let a = Coord::new(x, y);
+                if !map[a].0 {
+                    continue;
+                }
+                for (b, _) in map.walkable_tiles(a, movement) {
+                    if b < a {
+                        continue;
+                    }
+                    // Without the step between a and b, b should no longer be reachable.
+                    let mut seen = vec![a];
+                    let mut queue = vec![a];
+                    while let Some(current) = queue.pop() {
+                        for (n, _) in map.walkable_tiles(current, movement) {
+                            if (current == a && n == b) || seen.contains(&n) {
+                                continue;
+                            }
+                            seen.push(n);
+                            queue.push(n);
+                        }
+                    }
+                    if !seen.contains(&b) {
+                        bridges.push((a, b));
+                    }
+                }
+            }
+        }
+        bridges.sort_unstable_by_key(|(a, b)| (a.y, a.x, b.y, b.x));
+        prop_assert_eq!(map.bridges(movement), bridges);
+    }
+}
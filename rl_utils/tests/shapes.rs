@@ -0,0 +1,148 @@
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use rl_utils::{
+    ring, spiral, Area, BresenhamLine, Coord, Distance, Ellipse, SupercoverLine, SymmetricLine,
+};
+
+fn coord() -> impl Strategy<Value = Coord> {
+    (-20isize..20, -20isize..20).prop_map(Coord::from)
+}
+
+fn connected(line: &[Coord]) -> bool {
+    line.windows(2)
+        .all(|pair| pair[0] != pair[1] && pair[0].is_neightbour(pair[1]))
+}
+
+#[test]
+fn lines() {
+    assert_eq!(
+        BresenhamLine::new((0, 0).into(), (4, 2).into()).collect::<Vec<_>>(),
+        vec![
+            Coord::new(0, 0),
+            Coord::new(1, 1),
+            Coord::new(2, 1),
+            Coord::new(3, 2),
+            Coord::new(4, 2),
+        ]
+    );
+    assert_eq!(
+        SupercoverLine::new((0, 0).into(), (2, 1).into()).collect::<Vec<_>>(),
+        vec![
+            Coord::new(0, 0),
+            Coord::new(1, 0),
+            Coord::new(1, 1),
+            Coord::new(2, 1),
+        ]
+    );
+    assert_eq!(
+        SupercoverLine::new((0, 0).into(), (2, 2).into()).collect::<Vec<_>>(),
+        vec![Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2)]
+    );
+    assert_eq!(
+        SymmetricLine::new((3, 3).into(), (3, 3).into()).collect::<Vec<_>>(),
+        vec![Coord::new(3, 3)]
+    );
+}
+
+#[test]
+fn circles_follow_the_vision_radius_rules() {
+    let center = Coord::new(5, -2);
+    assert_eq!(Ellipse::circle(center, 0).filled().count(), 1);
+    assert_eq!(Ellipse::circle(center, 1).filled().count(), 9);
+    assert_eq!(Ellipse::circle(center, 2).filled().count(), 21);
+    assert_eq!(
+        Ellipse::circle(center, 2)
+            .with_strict(true)
+            .filled()
+            .count(),
+        13
+    );
+
+    for radius in 0..8 {
+        let circle = Ellipse::circle(center, radius);
+        for p in circle.bounds().outset(1).iter() {
+            let d = p.delta_abs(center);
+            assert_eq!(
+                circle.contains(p),
+                d.x * d.x + d.y * d.y <= radius * radius + radius
+            );
+        }
+    }
+
+    let ellipse = Ellipse::new(center, (6, 2).into());
+    let filled: BTreeSet<Coord> = ellipse.filled().collect();
+    let outline: BTreeSet<Coord> = ellipse.outline().collect();
+    assert!(outline.is_subset(&filled));
+    assert!(filled.contains(&(center + (6, 0).into())));
+    assert!(!filled.contains(&(center + (6, 1).into())));
+    assert!(!outline.contains(&center));
+    assert!(ellipse
+        .bounds()
+        .area_within(Area::new(center - (6, 2).into(), (13, 5).into())));
+}
+
+#[test]
+fn spirals_and_rings() {
+    let center = Coord::new(-3, 4);
+    let tiles: Vec<Coord> = spiral(center).take(49).collect();
+    assert_eq!(tiles[0], center);
+    assert_eq!(tiles[1], center - (1, 1).into());
+    assert_eq!(
+        tiles.iter().copied().collect::<BTreeSet<_>>(),
+        Area::new(center - (3, 3).into(), (7, 7).into())
+            .iter()
+            .collect()
+    );
+
+    assert_eq!(ring(center, 3, Distance::Chebyshev).count(), 24);
+    assert_eq!(ring(center, 3, Distance::Manhattan).count(), 12);
+    assert_eq!(
+        ring(center, 0, Distance::Euclidean).collect::<Vec<_>>(),
+        vec![center]
+    );
+
+    // Every tile lies on exactly one ring.
+    let mut covered = BTreeSet::new();
+    for radius in 0..=6 {
+        for p in ring(center, radius, Distance::Euclidean) {
+            assert_eq!(p.real_pyth(center).round() as isize, radius);
+            assert!(covered.insert(p));
+        }
+    }
+    let expected: BTreeSet<Coord> = Area::new(center - (6, 6).into(), (13, 13).into())
+        .iter()
+        .filter(|p| p.real_pyth(center).round() <= 6.0)
+        .collect();
+    assert_eq!(covered, expected);
+}
+
+proptest! {
+    #[test]
+    fn line_properties(start in coord(), end in coord()) {
+        let d = start.delta_abs(end);
+
+        let bresenham: Vec<Coord> = BresenhamLine::new(start, end).collect();
+        prop_assert_eq!(bresenham.len() as isize, start.pyth(end) + 1);
+        prop_assert_eq!(bresenham[0], start);
+        prop_assert_eq!(*bresenham.last().unwrap(), end);
+        prop_assert!(connected(&bresenham));
+
+        let supercover: Vec<Coord> = SupercoverLine::new(start, end).collect();
+        prop_assert_eq!(supercover[0], start);
+        prop_assert_eq!(*supercover.last().unwrap(), end);
+        prop_assert!(connected(&supercover));
+        prop_assert!(supercover.len() as isize > d.x.max(d.y));
+        prop_assert!(supercover.len() as isize <= d.x + d.y + 1);
+
+        let symmetric: Vec<Coord> = SymmetricLine::new(start, end).collect();
+        let mut reversed: Vec<Coord> = SymmetricLine::new(end, start).collect();
+        reversed.reverse();
+        prop_assert_eq!(symmetric.len() as isize, start.pyth(end) + 1);
+        prop_assert_eq!(SymmetricLine::new(start, end).size_hint().0, symmetric.len());
+        prop_assert_eq!(&symmetric, &reversed);
+        prop_assert_eq!(symmetric[0], start);
+        prop_assert_eq!(*symmetric.last().unwrap(), end);
+        prop_assert!(connected(&symmetric));
+    }
+}
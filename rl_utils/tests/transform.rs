@@ -0,0 +1,84 @@
+mod common;
+
+use common::numbered;
+use proptest::prelude::*;
+use rl_utils::{Area, Coord, Map, Rotation};
+
+fn rows(map: &Map<isize>) -> Vec<Vec<isize>> {
+    map.data
+        .chunks(map.area.size.x as usize)
+        .map(|r| r.to_vec())
+        .collect()
+}
+
+#[test]
+fn rotations_and_mirrors() {
+    // 0 1 2
+    // 3 4 5
+    let map = numbered((3, 2).into(), (7, -2).into());
+
+    let cw90 = map.rotate(Rotation::Clockwise90);
+    assert_eq!(cw90.area, Area::new((7, -2).into(), (2, 3).into()));
+    assert_eq!(rows(&cw90), vec![vec![3, 0], vec![4, 1], vec![5, 2]]);
+    assert_eq!(
+        rows(&map.rotate(Rotation::Clockwise180)),
+        vec![vec![5, 4, 3], vec![2, 1, 0]]
+    );
+    assert_eq!(
+        rows(&map.rotate(Rotation::Clockwise270)),
+        vec![vec![2, 5], vec![1, 4], vec![0, 3]]
+    );
+    assert_eq!(
+        rows(&map.mirror_horizontal()),
+        vec![vec![2, 1, 0], vec![5, 4, 3]]
+    );
+    assert_eq!(
+        rows(&map.mirror_vertical()),
+        vec![vec![3, 4, 5], vec![0, 1, 2]]
+    );
+    assert_eq!(
+        rows(&map.transpose()),
+        vec![vec![0, 3], vec![1, 4], vec![2, 5]]
+    );
+}
+
+#[test]
+fn crop_blit_and_resize() {
+    let mut map = numbered((4, 3).into(), (10, 10).into());
+
+    let cropped = map.crop(Area::new((11, 11).into(), (2, 2).into())).unwrap();
+    assert_eq!(cropped.area.position, Coord::new(11, 11));
+    assert_eq!(rows(&cropped), vec![vec![5, 6], vec![9, 10]]);
+    assert_eq!(map.crop(Area::new((9, 11).into(), (2, 2).into())), None);
+
+    let mut stamp = Map::new((2, 2).into());
+    stamp.data = vec![Some(100), None, None, Some(200)];
+    map.blit(&stamp, (13, 11).into(), |old, new| new.unwrap_or(*old));
+    assert_eq!(
+        rows(&map),
+        vec![vec![0, 1, 2, 3], vec![4, 5, 6, 100], vec![8, 9, 10, 11]]
+    );
+
+    map.resize(Area::new((9, 11).into(), (3, 3).into()), -1);
+    assert_eq!(map.area, Area::new((9, 11).into(), (3, 3).into()));
+    assert_eq!(
+        rows(&map),
+        vec![vec![-1, 4, 5], vec![-1, 8, 9], vec![-1, -1, -1]]
+    );
+    assert_eq!(map[Coord::new(10, 12)], 8);
+}
+
+proptest! {
+    #[test]
+    fn transforms_compose(w in 1isize..9, h in 1isize..9, x in -9isize..9, y in -9isize..9) {
+        let map = numbered((w, h).into(), (x, y).into());
+        let cw90 = map.rotate(Rotation::Clockwise90);
+
+        prop_assert_eq!(&cw90.rotate(Rotation::Clockwise270), &map);
+        prop_assert_eq!(&cw90.rotate(Rotation::Clockwise90), &map.rotate(Rotation::Clockwise180));
+        prop_assert_eq!(&map.transpose().mirror_horizontal(), &cw90);
+        prop_assert_eq!(&map.mirror_horizontal().mirror_vertical(), &map.rotate(Rotation::Clockwise180));
+        prop_assert_eq!(&map.transpose().transpose(), &map);
+        prop_assert_eq!(map.crop(map.area), Some(map.clone()));
+    }
+}